@@ -1,28 +1,80 @@
 use anchor_lang::prelude::*;
 
-#[error_code]
-pub enum WhitelistTransferHookError {
-    #[msg("Address is already whitelisted")]
-    AlreadyWhitelisted,
+/// Declares the error enum and its `ALL` table from the same list of variants,
+/// so a new variant is picked up by `from_code` without a second list to maintain
+macro_rules! error_codes {
+    (
+        #[error_code]
+        pub enum $name:ident {
+            $( #[msg($msg:literal)] $variant:ident, )*
+        }
+    ) => {
+        #[error_code]
+        pub enum $name {
+            $( #[msg($msg)] $variant, )*
+        }
 
-    #[msg("Address is not whitelisted")]
-    NotWhitelisted,
+        impl $name {
+            /// Every variant, in declaration order
+            pub const ALL: &'static [Self] = &[$( Self::$variant ),*];
+        }
+    };
+}
+
+error_codes! {
+    #[error_code]
+    pub enum WhitelistTransferHookError {
+        #[msg("Address is already whitelisted")]
+        AlreadyWhitelisted,
+
+        #[msg("Address is not whitelisted")]
+        NotWhitelisted,
+
+        #[msg("Transfer amount exceeds limit")]
+        ExceedsLimit,
+
+        #[msg("Insufficient funds in vault")]
+        InsufficientFunds,
+
+        #[msg("Arithmetic overflow")]
+        ArithmeticOverflow,
 
-    #[msg("Transfer amount exceeds limit")]
-    ExceedsLimit,
+        #[msg("Unauthorized: Only admin can perform this action")]
+        Unauthorized,
 
-    #[msg("Insufficient funds in vault")]
-    InsufficientFunds,
+        #[msg("Invalid account data")]
+        InvalidAccountData,
 
-    #[msg("Arithmetic overflow")]
-    ArithmeticOverflow,
+        #[msg("Account not enough keys")]
+        AccountNotEnoughKeys,
 
-    #[msg("Unauthorized: Only admin can perform this action")]
-    Unauthorized,
+        #[msg("TransferHook: Not transferring")]
+        NotTransferring,
 
-    #[msg("Invalid account data")]
-    InvalidAccountData,
+        #[msg("Extra account metas could not be built or written")]
+        InvalidExtraAccountMetas,
+
+        #[msg("Role is already granted to this member")]
+        RoleAlreadyGranted,
+
+        #[msg("Role is not granted to this member")]
+        RoleNotGranted,
+
+        #[msg("No room left for role grants")]
+        RoleGrantsFull,
+
+        #[msg("Vault deposits and withdrawals are paused")]
+        VaultPaused,
+
+        #[msg("Transfers are paused")]
+        TransferPaused,
+    }
+}
 
-    #[msg("Account not enough keys")]
-    AccountNotEnoughKeys,
+impl WhitelistTransferHookError {
+    /// Maps the custom error code of a failed instruction of this program back to its variant,
+    /// `None` for codes raised by Anchor, the token program or the runtime
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.iter().copied().find(|error| u32::from(*error) == code)
+    }
 }
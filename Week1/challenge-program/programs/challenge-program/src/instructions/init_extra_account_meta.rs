@@ -6,6 +6,8 @@ use spl_tlv_account_resolution::{
     seeds::Seed,
 };
 
+use crate::errors::WhitelistTransferHookError;
+
 
 #[derive(Accounts)]
 pub struct InitializeExtraAccountMetaList<'info> {
@@ -19,7 +21,7 @@ pub struct InitializeExtraAccountMetaList<'info> {
         bump,
         space = ExtraAccountMetaList::size_of(
             InitializeExtraAccountMetaList::extra_account_metas()?.len()
-        ).unwrap(),
+        ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
         payer = payer
     )]
     pub extra_account_meta_list: AccountInfo<'info>,
@@ -41,7 +43,7 @@ impl<'info> InitializeExtraAccountMetaList<'info> {
             ],
             false, // is_signer
             false, // is_writable
-        ).unwrap(),
+        ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
         
         // Destination whitelist
         ExtraAccountMeta::new_with_seeds(
@@ -57,7 +59,50 @@ impl<'info> InitializeExtraAccountMetaList<'info> {
             ],
             false, // is_signer
             false, // is_writable
-        ).unwrap(),
+        ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+
+        // Source token account owner's transfer limits, writable so the hook can accumulate usage
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"limit".to_vec(),
+                },
+                Seed::AccountData {
+                    account_index: 0,  // source token account
+                    data_index: 32,     // owner field offset in token account
+                    length: 32,         // pubkey length
+                },
+            ],
+            false, // is_signer
+            true,  // is_writable
+        ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+
+        // Source token account owner's whitelist, differs from the source whitelist when a delegate signs
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"whitelist".to_vec(),
+                },
+                Seed::AccountData {
+                    account_index: 0,  // source token account
+                    data_index: 32,     // owner field offset in token account
+                    length: 32,         // pubkey length
+                },
+            ],
+            false, // is_signer
+            false, // is_writable
+        ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+
+        // Config holding the delegate policy
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"config".to_vec(),
+                },
+            ],
+            false, // is_signer
+            false, // is_writable
+        ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
     ];
     
     Ok(account_metas)
@@ -1,15 +1,22 @@
 use anchor_lang::prelude::*;
 
-use crate::states::Whitelist;
+use crate::errors::WhitelistTransferHookError;
+use crate::states::{Config, Role, Whitelist};
 
 #[derive(Accounts)]
 #[instruction(user: Pubkey)]
 pub struct InitializeWhitelist<'info> {
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub operator: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.has_role(Role::WhitelistOperator, &operator.key()) @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
     #[account(
         init,
-        payer = admin,
+        payer = operator,
         space = Whitelist::INIT_SPACE + Whitelist::DISCRIMINATOR.len(), // 8 bytes for discriminator,32 bytes for address, 4 bytes for vector length, 1 byte for bump
         seeds = [b"whitelist", user.key().as_ref()],
         bump
@@ -25,11 +32,18 @@ impl<'info> InitializeWhitelist<'info> {
         user: Pubkey,
         amount: u64
     ) -> Result<()> {
+        // An opening balance carries a deposit over from a previous deployment, which only the
+        // super-admin may vouch for
+        require!(
+            amount == 0 || self.operator.key() == self.config.super_admin,
+            WhitelistTransferHookError::Unauthorized
+        );
+
         // Initialize the whitelist with an empty address vector
         self.whitelist.address = user.key();
         self.whitelist.bump = bumps.whitelist;
         self.whitelist.is_whitelisted = false;
-        self.whitelist.deposited_amount = 0;
+        self.whitelist.deposited_amount = amount;
         Ok(())
     }
 }
\ No newline at end of file
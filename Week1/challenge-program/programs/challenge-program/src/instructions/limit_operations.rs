@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::WhitelistTransferHookError;
+use crate::states::{Config, LimitWindow, Role, TransferLimit};
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct SetTransferLimit<'info> {
+    #[account(mut)]
+    pub limit_manager: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.has_role(Role::LimitManager, &limit_manager.key()) @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init_if_needed,
+        payer = limit_manager,
+        space = TransferLimit::INIT_SPACE + TransferLimit::DISCRIMINATOR.len(),
+        seeds = [b"limit", user.key().as_ref()],
+        bump
+    )]
+    pub transfer_limit: Account<'info, TransferLimit>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetTransferLimit<'info> {
+    pub fn set_transfer_limit(
+        &mut self,
+        bumps: SetTransferLimitBumps,
+        user: Pubkey,
+        max_per_transfer: u64,
+        window_limit: u64,
+        window: LimitWindow,
+    ) -> Result<()> {
+        // Switching the window kind restarts the usage count
+        let reset = self.transfer_limit.owner != user || self.transfer_limit.window != window;
+
+        self.transfer_limit.owner = user;
+        self.transfer_limit.max_per_transfer = max_per_transfer;
+        self.transfer_limit.window_limit = window_limit;
+        self.transfer_limit.window = window;
+        self.transfer_limit.bump = bumps.transfer_limit;
+
+        if reset {
+            self.transfer_limit.window_id = 0;
+            self.transfer_limit.window_used = 0;
+        }
+
+        msg!(
+            "Limits for {}: {} per transfer, {} per {:?}",
+            user,
+            max_per_transfer,
+            window_limit,
+            window
+        );
+
+        Ok(())
+    }
+}
@@ -1,13 +1,19 @@
 pub mod init_extra_account_meta;
+pub mod update_extra_account_meta;
 pub mod transfer_hook;
 pub mod initialize_whitelist;
 pub mod whitelist_operations;
 pub mod init_vault;
 pub mod vault_operation;
+pub mod limit_operations;
+pub mod role_operations;
 
 pub use init_extra_account_meta::*;
+pub use update_extra_account_meta::*;
 pub use transfer_hook::*;
 pub use initialize_whitelist::*;
 pub use whitelist_operations::*;
 pub use init_vault::*;
-pub use vault_operation::*;
\ No newline at end of file
+pub use vault_operation::*;
+pub use limit_operations::*;
+pub use role_operations::*;
\ No newline at end of file
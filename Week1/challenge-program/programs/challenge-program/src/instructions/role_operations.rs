@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::WhitelistTransferHookError;
+use crate::states::{Config, DelegatePolicy, Role, RoleGrant, VaultState};
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    // The vault authority becomes the super-admin
+    #[account(
+        mut,
+        address = vault_state.authority @ WhitelistTransferHookError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"vault_state"],
+        bump = vault_state.state_bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    #[account(
+        init,
+        payer = authority,
+        space = Config::INIT_SPACE + Config::DISCRIMINATOR.len(),
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConfig<'info> {
+    pub fn initialize_config(&mut self, bumps: &InitializeConfigBumps) -> Result<()> {
+        self.config.set_inner(Config {
+            super_admin: self.authority.key(),
+            roles: Vec::new(),
+            paused: false,
+            recovery_wallet: None,
+            delegate_policy: DelegatePolicy::Both,
+            bump: bumps.config,
+        });
+
+        msg!("Config initialized, super-admin: {}", self.authority.key());
+        Ok(())
+    }
+}
+
+/// Super-admin operations on the config: roles, the delegate policy and the recovery wallet
+#[derive(Accounts)]
+pub struct RoleOperations<'info> {
+    pub super_admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = super_admin @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> RoleOperations<'info> {
+    pub fn grant_role(&mut self, role: Role, member: Pubkey) -> Result<()> {
+        let grant = RoleGrant { member, role };
+        require!(
+            !self.config.roles.contains(&grant),
+            WhitelistTransferHookError::RoleAlreadyGranted
+        );
+        require!(
+            self.config.roles.len() < Config::MAX_ROLE_GRANTS,
+            WhitelistTransferHookError::RoleGrantsFull
+        );
+
+        self.config.roles.push(grant);
+
+        msg!("Granted {:?} to {}", role, member);
+        Ok(())
+    }
+
+    pub fn revoke_role(&mut self, role: Role, member: Pubkey) -> Result<()> {
+        let index = self
+            .config
+            .roles
+            .iter()
+            .position(|grant| grant == &RoleGrant { member, role })
+            .ok_or(WhitelistTransferHookError::RoleNotGranted)?;
+
+        self.config.roles.swap_remove(index);
+
+        msg!("Revoked {:?} from {}", role, member);
+        Ok(())
+    }
+
+    pub fn set_delegate_policy(&mut self, delegate_policy: DelegatePolicy) -> Result<()> {
+        self.config.delegate_policy = delegate_policy;
+        msg!("Delegate policy set to {:?}", delegate_policy);
+        Ok(())
+    }
+
+    pub fn set_recovery_wallet(&mut self, recovery_wallet: Option<Pubkey>) -> Result<()> {
+        self.config.recovery_wallet = recovery_wallet;
+        msg!("Recovery wallet set to {:?}", recovery_wallet);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub pauser: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.has_role(Role::Pauser, &pauser.key()) @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> SetPaused<'info> {
+    pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+        self.config.paused = paused;
+        msg!("Vault paused: {} by {}", paused, self.pauser.key());
+        Ok(())
+    }
+}
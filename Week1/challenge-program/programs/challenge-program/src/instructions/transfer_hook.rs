@@ -16,14 +16,14 @@ use anchor_spl::{
     }
 };
 
-use crate::states::Whitelist;
+use crate::states::{Config, DelegatePolicy, TransferLimit, Whitelist};
 use crate::errors::WhitelistTransferHookError;
 
 #[derive(Accounts)]
 pub struct TransferHook<'info> {
+    // The authority is checked by the token program, it may be the owner or a delegate
     #[account(
         token::mint = mint, 
-        token::authority = owner,
     )]
     pub source_token: InterfaceAccount<'info, TokenAccount>,
     pub mint: InterfaceAccount<'info, Mint>,
@@ -31,7 +31,8 @@ pub struct TransferHook<'info> {
         token::mint = mint,
     )]
     pub destination_token: InterfaceAccount<'info, TokenAccount>,
-    /// CHECK: source token account owner, can be SystemAccount or PDA owned by another program
+    /// CHECK: transfer authority, the source token account owner or a delegate (the user withdrawing
+    /// from the vault). Can be SystemAccount or PDA owned by another program
     pub owner: UncheckedAccount<'info>,
     /// CHECK: ExtraAccountMetaList Account,
     #[account(
@@ -39,9 +40,9 @@ pub struct TransferHook<'info> {
         bump
     )]
     pub extra_account_meta_list: UncheckedAccount<'info>,
-    // Source whitelist
+    // Transfer authority's whitelist
     #[account(
-        seeds = [b"whitelist", source_token.owner.key().as_ref()], 
+        seeds = [b"whitelist", owner.key().as_ref()], 
         bump = source_whitelist.bump,
      )]
     pub source_whitelist: Account<'info, Whitelist>,
@@ -52,6 +53,29 @@ pub struct TransferHook<'info> {
         bump = dest_whitelist.bump,
      )]
      pub dest_whitelist: Account<'info, Whitelist>,
+
+    /// CHECK: Source token account owner's transfer limits, no limits apply until the admin creates it.
+    /// Keyed by the owner rather than the authority, so a delegate cannot move tokens past them.
+    #[account(
+        mut,
+        seeds = [b"limit", source_token.owner.as_ref()], 
+        bump,
+     )]
+    pub transfer_limit: UncheckedAccount<'info>,
+
+    /// CHECK: Source token account owner's whitelist, may not exist (e.g. the vault state PDA)
+    #[account(
+        seeds = [b"whitelist", source_token.owner.as_ref()], 
+        bump,
+     )]
+    pub owner_whitelist: UncheckedAccount<'info>,
+
+    /// CHECK: Config holding the pause switch and the delegate policy, may not exist
+    #[account(
+        seeds = [b"config"], 
+        bump,
+     )]
+    pub config: UncheckedAccount<'info>,
 }
 
 impl<'info> TransferHook<'info> {
@@ -64,8 +88,19 @@ impl<'info> TransferHook<'info> {
         msg!("Source token owner: {}", self.source_token.owner);
         msg!("Destination token owner: {}", self.destination_token.owner);
 
+        let config = Config::load(&self.config)?;
+        if let Some(config) = config.as_ref().filter(|config| config.paused) {
+            require!(
+                config.is_recovery_transfer(&self.source_token.owner, &self.destination_token.owner),
+                WhitelistTransferHookError::TransferPaused
+            );
+            msg!("Transfer allowed while paused: recovery wallet involved");
+            return Ok(());
+        }
+        let delegate_policy = config.map_or(DelegatePolicy::Both, |config| config.delegate_policy);
+
        // Checks if source is whitelisted
-       let source_ok = self.source_whitelist.is_whitelisted;
+       let source_ok = self.source_is_whitelisted(delegate_policy)?;
        // Checks if destination is whitelisted
        let dest_ok = self.dest_whitelist.is_whitelisted;
        
@@ -81,6 +116,49 @@ impl<'info> TransferHook<'info> {
            msg!("Transfer allowed: Destination {} is whitelisted", self.destination_token.owner);
        }
 
+        self.enforce_limits(amount)?;
+
+        Ok(())
+    }
+
+    /// Whether the source side passes, which depends on the delegate policy when a delegate signs
+    fn source_is_whitelisted(&self, delegate_policy: DelegatePolicy) -> Result<bool> {
+        let authority_ok = self.source_whitelist.is_whitelisted;
+        if self.owner.key() == self.source_token.owner {
+            return Ok(authority_ok);
+        }
+
+        let owner_info = self.owner_whitelist.to_account_info();
+        let owner_ok = owner_info.owner == &crate::ID
+            && !owner_info.data_is_empty()
+            && Whitelist::try_deserialize(&mut &owner_info.try_borrow_data()?[..])?.is_whitelisted;
+
+        msg!("Delegated transfer by {}, policy {:?}", self.owner.key(), delegate_policy);
+
+        Ok(match delegate_policy {
+            DelegatePolicy::Delegate => authority_ok,
+            DelegatePolicy::Owner => owner_ok,
+            DelegatePolicy::Both => authority_ok && owner_ok,
+        })
+    }
+
+    /// Checks the amount against the owner's limits and accumulates it in the current window.
+    fn enforce_limits(&mut self, amount: u64) -> Result<()> {
+        let limit_info = self.transfer_limit.to_account_info();
+        if limit_info.owner != &crate::ID || limit_info.data_is_empty() {
+            return Ok(());
+        }
+
+        let mut transfer_limit = TransferLimit::try_deserialize(&mut &limit_info.try_borrow_data()?[..])?;
+        transfer_limit.record_transfer(amount, &Clock::get()?)?;
+        transfer_limit.try_serialize(&mut &mut limit_info.try_borrow_mut_data()?[..])?;
+
+        msg!(
+            "Transfer limit usage: {}/{}",
+            transfer_limit.window_used,
+            transfer_limit.window_limit
+        );
+
         Ok(())
     }
 
@@ -103,9 +181,10 @@ impl<'info> TransferHook<'info> {
         let account_extension = account.get_extension_mut::<TransferHookAccount>()?;
     
         // Check if the account is in the middle of a transfer operation
-        if !bool::from(account_extension.transferring) {
-            panic!("TransferHook: Not transferring");
-        }
+        require!(
+            bool::from(account_extension.transferring),
+            WhitelistTransferHookError::NotTransferring
+        );
     
         Ok(())
     }
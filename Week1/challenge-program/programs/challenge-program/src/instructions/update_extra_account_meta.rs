@@ -0,0 +1,81 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token_interface::Mint;
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+use crate::instructions::InitializeExtraAccountMetaList;
+use crate::states::Config;
+use crate::errors::WhitelistTransferHookError;
+
+#[derive(Accounts)]
+pub struct UpdateExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub super_admin: Signer<'info>,
+    /// CHECK: ExtraAccountMetaList Account, must use these seeds
+    #[account(
+        mut,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = super_admin @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UpdateExtraAccountMetaList<'info> {
+    /// Rewrites the mint's list with the extra accounts this version of the hook resolves,
+    /// so mints initialized before the limit seeds changed resolve the source owner's limit.
+    pub fn update_extra_account_metas(&mut self) -> Result<()> {
+        let extra_account_metas = InitializeExtraAccountMetaList::extra_account_metas()?;
+        let new_len = ExtraAccountMetaList::size_of(extra_account_metas.len()).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?;
+
+        let info = self.extra_account_meta_list.to_account_info();
+        let current_len = info.data_len();
+        let rent = Rent::get()?;
+
+        // Grow before writing the list, the update shifts data within the existing buffer
+        if new_len > current_len {
+            let lamports = rent.minimum_balance(new_len).saturating_sub(info.lamports());
+            if lamports > 0 {
+                transfer(
+                    CpiContext::new(
+                        self.system_program.to_account_info(),
+                        Transfer {
+                            from: self.super_admin.to_account_info(),
+                            to: info.clone(),
+                        },
+                    ),
+                    lamports,
+                )?;
+            }
+            info.realloc(new_len, false)?;
+        }
+
+        ExtraAccountMetaList::update::<ExecuteInstruction>(
+            &mut info.try_borrow_mut_data()?,
+            &extra_account_metas
+        ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?;
+
+        // Shrink after writing, and hand the freed rent back to the super-admin
+        if new_len < current_len {
+            info.realloc(new_len, false)?;
+
+            let excess = info.lamports().saturating_sub(rent.minimum_balance(new_len));
+            **info.try_borrow_mut_lamports()? -= excess;
+            **self.super_admin.to_account_info().try_borrow_mut_lamports()? += excess;
+        }
+
+        msg!("Extra Account Metas Length: {}", extra_account_metas.len());
+
+        Ok(())
+    }
+}
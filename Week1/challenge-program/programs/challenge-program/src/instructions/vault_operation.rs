@@ -9,7 +9,7 @@ use anchor_spl::{
     token_2022::Token2022,
 };
 
-use crate::states::{VaultState, Whitelist};
+use crate::states::{Config, VaultState, Whitelist};
 use crate::errors::WhitelistTransferHookError;
 
 //  DEPOSIT
@@ -57,6 +57,13 @@ pub struct Deposit<'info> {
     )]
     pub whitelist: Account<'info, Whitelist>,
     
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ WhitelistTransferHookError::VaultPaused,
+    )]
+    pub config: Account<'info, Config>,
+    
     pub token_program: Program<'info, Token2022>,
 }
 
@@ -113,6 +120,13 @@ pub struct Withdraw<'info> {
     )]
     pub whitelist: Account<'info, Whitelist>,
     
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ WhitelistTransferHookError::VaultPaused,
+    )]
+    pub config: Account<'info, Config>,
+    
     pub token_program: Program<'info, Token2022>,
 }
 
@@ -3,15 +3,12 @@ use anchor_lang::{
 };
 
 use crate::errors::WhitelistTransferHookError;
-use crate::states::{whitelist::Whitelist, VaultState};
+use crate::states::{whitelist::Whitelist, Config, Role};
 
 #[derive(Accounts)]
 pub struct WhitelistOperations<'info> {
-    #[account(
-        mut,
-        address = vault_state.authority @ WhitelistTransferHookError::Unauthorized
-    )]
-    pub admin: Signer<'info>,
+    #[account(mut)]
+    pub operator: Signer<'info>,
     #[account(
         mut,
         seeds = [b"whitelist", user.key().as_ref()],
@@ -19,10 +16,11 @@ pub struct WhitelistOperations<'info> {
     )]
     pub whitelist: Account<'info, Whitelist>,
     #[account(
-        seeds = [b"vault_state"],
-        bump = vault_state.state_bump,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.has_role(Role::WhitelistOperator, &operator.key()) @ WhitelistTransferHookError::Unauthorized,
     )]
-    pub vault_state: Account<'info, VaultState>,
+    pub config: Account<'info, Config>,
     #[account(mut)]
     pub user: SystemAccount<'info>,
     pub system_program: Program<'info, System>,
@@ -34,6 +32,7 @@ impl<'info> WhitelistOperations<'info> {
             Err(WhitelistTransferHookError::AlreadyWhitelisted)?
         }
         self.whitelist.is_whitelisted = true;
+        msg!("Added to whitelist: {}", user);
         Ok(())
     }
 
@@ -42,6 +41,7 @@ impl<'info> WhitelistOperations<'info> {
             Err(WhitelistTransferHookError::NotWhitelisted)?
         }
         self.whitelist.is_whitelisted = false;
+        msg!("Removed from whitelist: {}", address);
         Ok(())
     }
 
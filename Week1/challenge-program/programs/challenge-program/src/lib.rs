@@ -2,9 +2,13 @@
 #![allow(deprecated)]
 
 mod instructions;
-mod states;
-mod errors;
+pub mod states;
+pub mod errors;
+#[cfg(test)]
+mod tests;
 use instructions::*;
+use errors::WhitelistTransferHookError;
+use states::{DelegatePolicy, LimitWindow, Role};
 use spl_discriminator::SplDiscriminate;
 use spl_transfer_hook_interface::{
     instruction::{
@@ -20,6 +24,36 @@ declare_id!("G3Z36nvjRzn7F4bfn4mMovi1MJEUYhCXcv6xHotrAd9B");
 pub mod challenge_program {
     use super::*;
 
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        ctx.accounts.initialize_config(&ctx.bumps)
+    }
+
+    pub fn grant_role(ctx: Context<RoleOperations>, role: Role, member: Pubkey) -> Result<()> {
+        ctx.accounts.grant_role(role, member)
+    }
+
+    pub fn revoke_role(ctx: Context<RoleOperations>, role: Role, member: Pubkey) -> Result<()> {
+        ctx.accounts.revoke_role(role, member)
+    }
+
+    pub fn set_delegate_policy(
+        ctx: Context<RoleOperations>,
+        delegate_policy: DelegatePolicy,
+    ) -> Result<()> {
+        ctx.accounts.set_delegate_policy(delegate_policy)
+    }
+
+    pub fn set_recovery_wallet(
+        ctx: Context<RoleOperations>,
+        recovery_wallet: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.set_recovery_wallet(recovery_wallet)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.set_paused(paused)
+    }
+
     pub fn initialize_whitelist(
         ctx: Context<InitializeWhitelist>,
         user: Pubkey,
@@ -42,6 +76,16 @@ pub mod challenge_program {
         ctx.accounts.remove_from_whitelist(user)
     }
 
+    pub fn set_transfer_limit(
+        ctx: Context<SetTransferLimit>,
+        user: Pubkey,
+        max_per_transfer: u64,
+        window_limit: u64,
+        window: LimitWindow,
+    ) -> Result<()> {
+        ctx.accounts.set_transfer_limit(ctx.bumps, user, max_per_transfer, window_limit, window)
+    }
+
     pub fn initialize_vault(
         ctx: Context<InitializeVault>,
         initial_supply: u64,
@@ -78,11 +122,17 @@ pub mod challenge_program {
         ExtraAccountMetaList::init::<ExecuteInstruction>(
             &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
             &extra_account_metas
-        ).unwrap();
+        ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?;
 
         Ok(())
     }
 
+    pub fn update_extra_account_metas(
+        ctx: Context<UpdateExtraAccountMetaList>
+    ) -> Result<()> {
+        ctx.accounts.update_extra_account_metas()
+    }
+
     #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
     pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
         // Call the transfer hook logic
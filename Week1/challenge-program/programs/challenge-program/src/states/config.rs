@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+/// Permission the super-admin can grant to other keys. The super-admin itself holds every role.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum Role {
+    /// May create whitelist entries and add or remove users
+    WhitelistOperator,
+    /// May pause and unpause vault deposits, withdrawals and transfers of the vault mint
+    Pauser,
+    /// May set per-owner transfer limits
+    LimitManager,
+}
+
+/// Whose whitelist entry stands for the source side when a delegate, rather than the owner, signs a transfer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum DelegatePolicy {
+    /// The delegate moving the tokens, e.g. the user withdrawing from the vault
+    Delegate,
+    /// The owner of the source token account
+    Owner,
+    /// Both the delegate and the owner
+    Both,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct RoleGrant {
+    pub member: Pubkey,
+    pub role: Role,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub super_admin: Pubkey,
+    #[max_len(16)]
+    pub roles: Vec<RoleGrant>,
+    pub paused: bool,
+    // Still allowed to send or receive while paused, to move funds during an incident
+    pub recovery_wallet: Option<Pubkey>,
+    pub delegate_policy: DelegatePolicy,
+    pub bump: u8,
+}
+
+impl Config {
+    pub const MAX_ROLE_GRANTS: usize = 16;
+
+    /// Whether `member` may act in `role`
+    pub fn has_role(&self, role: Role, member: &Pubkey) -> bool {
+        &self.super_admin == member || self.roles.contains(&RoleGrant { member: *member, role })
+    }
+
+    /// Whether a paused vault still lets this transfer through, because the recovery wallet is involved
+    pub fn is_recovery_transfer(&self, source_owner: &Pubkey, destination_owner: &Pubkey) -> bool {
+        self.recovery_wallet
+            .is_some_and(|wallet| &wallet == source_owner || &wallet == destination_owner)
+    }
+
+    /// Reads the config resolved by the hook, `None` until it is initialized
+    pub fn load(info: &AccountInfo) -> Result<Option<Config>> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Config::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+    }
+}
@@ -1,5 +1,9 @@
 pub mod whitelist;
 pub mod vault;
+pub mod transfer_limit;
+pub mod config;
 
 pub use whitelist::*;
-pub use vault::*;
\ No newline at end of file
+pub use vault::*;
+pub use transfer_limit::*;
+pub use config::*;
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::WhitelistTransferHookError;
+
+/// Period after which the rolling usage of a wallet resets
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum LimitWindow {
+    Epoch,
+    Day,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TransferLimit {
+    pub owner: Pubkey,
+    pub max_per_transfer: u64,   // Largest single transfer allowed
+    pub window_limit: u64,       // Total allowed per window
+    pub window: LimitWindow,
+    pub window_id: u64,          // Epoch or day the usage below belongs to
+    pub window_used: u64,        // Amount transferred in the current window
+    pub bump: u8,
+}
+
+impl TransferLimit {
+    const SECONDS_PER_DAY: i64 = 86_400;
+
+    fn current_window(&self, clock: &Clock) -> u64 {
+        match self.window {
+            LimitWindow::Epoch => clock.epoch,
+            LimitWindow::Day => clock.unix_timestamp.div_euclid(Self::SECONDS_PER_DAY) as u64,
+        }
+    }
+
+    /// Records a transfer, resetting the usage when a new window has started
+    pub fn record_transfer(&mut self, amount: u64, clock: &Clock) -> Result<()> {
+        require!(
+            amount <= self.max_per_transfer,
+            WhitelistTransferHookError::ExceedsLimit
+        );
+
+        let window_id = self.current_window(clock);
+        if window_id != self.window_id {
+            self.window_id = window_id;
+            self.window_used = 0;
+        }
+
+        let window_used = self
+            .window_used
+            .checked_add(amount)
+            .ok_or(WhitelistTransferHookError::ArithmeticOverflow)?;
+
+        require!(
+            window_used <= self.window_limit,
+            WhitelistTransferHookError::ExceedsLimit
+        );
+
+        self.window_used = window_used;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,877 @@
+#[cfg(test)]
+mod challenge_program_tests {
+
+    use {
+        anchor_lang::{
+            prelude::{msg, Clock, Pubkey},
+            system_program::ID as SYSTEM_PROGRAM_ID,
+            InstructionData, ToAccountMetas,
+        },
+        anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID,
+        litesvm::{types::FailedTransactionMetadata, LiteSVM},
+        litesvm_token::CreateAssociatedTokenAccount,
+        solana_address::Address,
+        solana_instruction::{error::InstructionError, AccountMeta, Instruction},
+        solana_keypair::Keypair,
+        solana_message::Message,
+        solana_native_token::LAMPORTS_PER_SOL,
+        solana_signer::Signer,
+        solana_transaction::{Transaction, TransactionError},
+        spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed},
+        spl_token_2022::{extension::StateWithExtensions, state::Account as TokenAccount},
+        std::path::PathBuf,
+    };
+
+    use crate::errors::WhitelistTransferHookError;
+    use crate::instructions::InitializeExtraAccountMetaList;
+    use crate::states::{Config, DelegatePolicy, LimitWindow, Role, TransferLimit};
+
+    static PROGRAM_ID: Pubkey = crate::ID;
+
+    const DECIMALS: u8 = 9;
+
+    // LiteSVM and the token program crates work on `Address`, the same 32 bytes as anchor's `Pubkey`
+    fn address(key: &Pubkey) -> Address {
+        Address::new_from_array(key.to_bytes())
+    }
+
+    fn pubkey(address: &Address) -> Pubkey {
+        Pubkey::new_from_array(address.to_bytes())
+    }
+
+    // Setup function to initialize LiteSVM, load the program and fund an admin keypair
+    fn setup() -> (LiteSVM, Keypair) {
+        let mut program = LiteSVM::new();
+        let admin = Keypair::new();
+
+        program
+            .airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to admin");
+
+        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../target/deploy/challenge_program.so");
+
+        let program_data = std::fs::read(so_path).expect("Failed to read program SO file");
+
+        program
+            .add_program(address(&PROGRAM_ID), &program_data)
+            .expect("Failed to add program");
+
+        (program, admin)
+    }
+
+    // An instruction of this program, from its anchor accounts and arguments
+    fn program_ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+        Instruction {
+            program_id: address(&PROGRAM_ID),
+            accounts: accounts
+                .to_account_metas(None)
+                .into_iter()
+                .map(|meta| AccountMeta {
+                    pubkey: address(&meta.pubkey),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: data.data(),
+        }
+    }
+
+    // Sends the instructions in a single transaction paid by the first signer
+    fn send(
+        program: &mut LiteSVM,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), Box<FailedTransactionMetadata>> {
+        let message = Message::new(instructions, Some(&signers[0].pubkey()));
+        let transaction = Transaction::new(signers, message, program.latest_blockhash());
+
+        let tx = program.send_transaction(transaction).map_err(Box::new)?;
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+        Ok(())
+    }
+
+    // Decodes the program error a failed transaction was rejected with
+    fn program_error(
+        result: Result<(), Box<FailedTransactionMetadata>>,
+    ) -> Option<WhitelistTransferHookError> {
+        match result.err()?.err {
+            TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+                WhitelistTransferHookError::from_code(code)
+            }
+            _ => None,
+        }
+    }
+
+    fn pda(seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &PROGRAM_ID).0
+    }
+
+    fn mint_pda() -> Pubkey {
+        pda(&[b"mint"])
+    }
+
+    fn vault_pda() -> Pubkey {
+        pda(&[b"vault", mint_pda().as_ref()])
+    }
+
+    fn vault_state_pda() -> Pubkey {
+        pda(&[b"vault_state"])
+    }
+
+    fn config_pda() -> Pubkey {
+        pda(&[b"config"])
+    }
+
+    fn whitelist_pda(user: &Pubkey) -> Pubkey {
+        pda(&[b"whitelist", user.as_ref()])
+    }
+
+    fn limit_pda(owner: &Pubkey) -> Pubkey {
+        pda(&[b"limit", owner.as_ref()])
+    }
+
+    fn extra_account_meta_list_pda() -> Pubkey {
+        pda(&[b"extra-account-metas", mint_pda().as_ref()])
+    }
+
+    fn initialize_config_ix(authority: &Pubkey) -> Instruction {
+        program_ix(
+            crate::accounts::InitializeConfig {
+                authority: *authority,
+                vault_state: vault_state_pda(),
+                config: config_pda(),
+                system_program: SYSTEM_PROGRAM_ID,
+            },
+            crate::instruction::InitializeConfig {},
+        )
+    }
+
+    fn role_operations_ix(super_admin: &Pubkey, data: impl InstructionData) -> Instruction {
+        program_ix(
+            crate::accounts::RoleOperations {
+                super_admin: *super_admin,
+                config: config_pda(),
+            },
+            data,
+        )
+    }
+
+    fn set_paused_ix(pauser: &Pubkey, paused: bool) -> Instruction {
+        program_ix(
+            crate::accounts::SetPaused {
+                pauser: *pauser,
+                config: config_pda(),
+            },
+            crate::instruction::SetPaused { paused },
+        )
+    }
+
+    fn initialize_whitelist_ix(operator: &Pubkey, user: &Pubkey) -> Instruction {
+        program_ix(
+            crate::accounts::InitializeWhitelist {
+                operator: *operator,
+                config: config_pda(),
+                whitelist: whitelist_pda(user),
+                system_program: SYSTEM_PROGRAM_ID,
+            },
+            crate::instruction::InitializeWhitelist { user: *user, amount: 0 },
+        )
+    }
+
+    fn whitelist_operations_ix(operator: &Pubkey, user: &Pubkey, data: impl InstructionData) -> Instruction {
+        program_ix(
+            crate::accounts::WhitelistOperations {
+                operator: *operator,
+                whitelist: whitelist_pda(user),
+                config: config_pda(),
+                user: *user,
+                system_program: SYSTEM_PROGRAM_ID,
+            },
+            data,
+        )
+    }
+
+    // Creates the entry of `user` and whitelists it
+    fn whitelist_user_ixs(operator: &Pubkey, user: &Pubkey) -> [Instruction; 2] {
+        [
+            initialize_whitelist_ix(operator, user),
+            whitelist_operations_ix(operator, user, crate::instruction::AddToWhitelist { user: *user }),
+        ]
+    }
+
+    fn deposit_ix(user: &Pubkey, user_token_account: &Pubkey, amount: u64) -> Instruction {
+        program_ix(
+            crate::accounts::Deposit {
+                user: *user,
+                mint: mint_pda(),
+                user_token_account: *user_token_account,
+                vault: vault_pda(),
+                vault_state: vault_state_pda(),
+                whitelist: whitelist_pda(user),
+                config: config_pda(),
+                token_program: TOKEN_2022_PROGRAM_ID,
+            },
+            crate::instruction::Deposit { amount },
+        )
+    }
+
+    fn withdraw_ix(user: &Pubkey, amount: u64) -> Instruction {
+        program_ix(
+            crate::accounts::Withdraw {
+                user: *user,
+                mint: mint_pda(),
+                vault: vault_pda(),
+                vault_state: vault_state_pda(),
+                whitelist: whitelist_pda(user),
+                config: config_pda(),
+                token_program: TOKEN_2022_PROGRAM_ID,
+            },
+            crate::instruction::Withdraw { amount },
+        )
+    }
+
+    // A transfer_checked of the vault mint with the extra accounts the hook resolves
+    fn hooked_transfer(
+        source: &Pubkey,
+        destination: &Pubkey,
+        source_owner: &Pubkey,
+        authority: &Pubkey,
+        destination_owner: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let mut transfer_ix = spl_token_2022::instruction::transfer_checked(
+            &address(&TOKEN_2022_PROGRAM_ID),
+            &address(source),
+            &address(&mint_pda()),
+            &address(destination),
+            &address(authority),
+            &[],
+            amount,
+            DECIMALS,
+        )
+        .unwrap();
+
+        transfer_ix.accounts.extend([
+            AccountMeta::new_readonly(address(&whitelist_pda(authority)), false),
+            AccountMeta::new_readonly(address(&whitelist_pda(destination_owner)), false),
+            AccountMeta::new(address(&limit_pda(source_owner)), false),
+            AccountMeta::new_readonly(address(&whitelist_pda(source_owner)), false),
+            AccountMeta::new_readonly(address(&config_pda()), false),
+            AccountMeta::new_readonly(address(&PROGRAM_ID), false),
+            AccountMeta::new_readonly(address(&extra_account_meta_list_pda()), false),
+        ]);
+
+        transfer_ix
+    }
+
+    fn token_balance(program: &LiteSVM, token_account: &Pubkey) -> u64 {
+        let account = program.get_account(&address(token_account)).unwrap();
+        StateWithExtensions::<TokenAccount>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    // Creates the vault and its hooked mint with `supply` in the vault, `authority` becoming the
+    // vault authority
+    fn initialize_vault_ixs(authority: &Pubkey, supply: u64) -> [Instruction; 2] {
+        [
+            program_ix(
+                crate::accounts::InitializeVault {
+                    authority: *authority,
+                    mint: mint_pda(),
+                    metadata: pda(&[b"metadata"]),
+                    vault: vault_pda(),
+                    vault_state: vault_state_pda(),
+                    token_program: TOKEN_2022_PROGRAM_ID,
+                    system_program: SYSTEM_PROGRAM_ID,
+                },
+                crate::instruction::InitializeVault { initial_supply: supply },
+            ),
+            program_ix(
+                crate::accounts::InitializeExtraAccountMetaList {
+                    payer: *authority,
+                    extra_account_meta_list: extra_account_meta_list_pda(),
+                    mint: mint_pda(),
+                    system_program: SYSTEM_PROGRAM_ID,
+                },
+                crate::instruction::InitializeTransferHook {},
+            ),
+        ]
+    }
+
+    // Creates the vault, its hooked mint with `supply` in the vault, the extra account metas and
+    // the config, all with `admin` as the authority
+    fn setup_vault(program: &mut LiteSVM, admin: &Keypair, supply: u64) {
+        let admin_key = pubkey(&admin.pubkey());
+        send(program, &initialize_vault_ixs(&admin_key, supply), &[admin]).unwrap();
+        send(program, &[initialize_config_ix(&admin_key)], &[admin]).unwrap();
+    }
+
+    fn grant_role_ix(super_admin: &Pubkey, role: Role, member: &Pubkey) -> Instruction {
+        role_operations_ix(super_admin, crate::instruction::GrantRole { role, member: *member })
+    }
+
+    fn revoke_role_ix(super_admin: &Pubkey, role: Role, member: &Pubkey) -> Instruction {
+        role_operations_ix(super_admin, crate::instruction::RevokeRole { role, member: *member })
+    }
+
+    fn set_transfer_limit_ix(limit_manager: &Pubkey, user: &Pubkey) -> Instruction {
+        program_ix(
+            crate::accounts::SetTransferLimit {
+                limit_manager: *limit_manager,
+                config: config_pda(),
+                transfer_limit: limit_pda(user),
+                system_program: SYSTEM_PROGRAM_ID,
+            },
+            crate::instruction::SetTransferLimit {
+                user: *user,
+                max_per_transfer: 100,
+                window_limit: 250,
+                window: LimitWindow::Day,
+            },
+        )
+    }
+
+    // A keypair with lamports to pay for its own transactions
+    fn funded_keypair(program: &mut LiteSVM) -> Keypair {
+        let keypair = Keypair::new();
+        program.airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        keypair
+    }
+
+    // Whitelists a new user and withdraws `amount` from the vault into its token account.
+    // Returns the user and its token account
+    fn funded_user(program: &mut LiteSVM, admin: &Keypair, amount: u64) -> (Keypair, Pubkey) {
+        let user = funded_keypair(program);
+        let user_key = pubkey(&user.pubkey());
+
+        let token_account = CreateAssociatedTokenAccount::new(program, admin, &address(&mint_pda()))
+            .owner(&user.pubkey())
+            .token_program_id(&address(&TOKEN_2022_PROGRAM_ID))
+            .send()
+            .unwrap();
+        let token_account = pubkey(&token_account);
+
+        send(program, &whitelist_user_ixs(&pubkey(&admin.pubkey()), &user_key), &[admin]).unwrap();
+        send(
+            program,
+            &[
+                deposit_ix(&user_key, &token_account, amount),
+                withdraw_ix(&user_key, amount),
+                hooked_transfer(&vault_pda(), &token_account, &vault_state_pda(), &user_key, &user_key, amount),
+            ],
+            &[&user],
+        )
+        .unwrap();
+
+        (user, token_account)
+    }
+
+    // Token account data with `owner` at the offset the extra account metas read it from
+    fn token_account_data(mint: &Pubkey, owner: &Pubkey) -> Vec<u8> {
+        let mut data = vec![0; 165];
+        data[..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        data
+    }
+
+    // Resolves the writable limit PDA of a transfer_checked signed by `authority`
+    fn resolve_limit_pda(
+        source_data: &[u8],
+        mint: &Pubkey,
+        destination_data: &[u8],
+        authority: &Pubkey,
+    ) -> Pubkey {
+        // spl-tlv-account-resolution works on `Address`, the same 32 bytes as anchor's `Pubkey`
+        let address = |key: &Pubkey| Address::new_from_array(key.to_bytes());
+        let keys = [
+            (address(&Pubkey::new_unique()), Some(source_data)),
+            (address(mint), None),
+            (address(&Pubkey::new_unique()), Some(destination_data)),
+            (address(authority), None),
+        ];
+
+        let limit_meta = InitializeExtraAccountMetaList::extra_account_metas()
+            .unwrap()
+            .into_iter()
+            .find(|meta| bool::from(meta.is_writable))
+            .unwrap();
+
+        let limit = limit_meta
+            .resolve(&[], &address(&PROGRAM_ID), |index| {
+                keys.get(index).map(|(key, data)| (key, *data))
+            })
+            .unwrap()
+            .pubkey;
+        Pubkey::new_from_array(limit.to_bytes())
+    }
+
+    #[test]
+    fn test_limit_follows_source_owner_not_delegate() {
+        let mint = Pubkey::new_unique();
+        let limited_owner = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+
+        let source_data = token_account_data(&mint, &limited_owner);
+        let destination_data = token_account_data(&mint, &recipient);
+        let owner_limit = Pubkey::find_program_address(&[b"limit", limited_owner.as_ref()], &PROGRAM_ID).0;
+
+        // The owner signing and a delegate it approved resolve to the same limit account,
+        // so approving a delegate without a limit does not lift the owner's limits
+        assert_eq!(resolve_limit_pda(&source_data, &mint, &destination_data, &limited_owner), owner_limit);
+        assert_eq!(resolve_limit_pda(&source_data, &mint, &destination_data, &delegate), owner_limit);
+
+        let limit_seeds = ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"limit".to_vec() },
+                Seed::AccountData { account_index: 0, data_index: 32, length: 32 },
+            ],
+            false,
+            true,
+        )
+        .unwrap();
+        assert!(InitializeExtraAccountMetaList::extra_account_metas().unwrap().contains(&limit_seeds));
+    }
+
+    fn limit(window: LimitWindow) -> TransferLimit {
+        TransferLimit {
+            owner: Pubkey::new_unique(),
+            max_per_transfer: 100,
+            window_limit: 250,
+            window,
+            window_id: 0,
+            window_used: 0,
+            bump: 255,
+        }
+    }
+
+    fn clock(epoch: u64, unix_timestamp: i64) -> Clock {
+        Clock {
+            slot: 0,
+            epoch_start_timestamp: 0,
+            epoch,
+            leader_schedule_epoch: epoch,
+            unix_timestamp,
+        }
+    }
+
+    #[test]
+    fn test_limit_per_transfer_cap() {
+        let mut limit = limit(LimitWindow::Epoch);
+        let now = clock(1, 0);
+
+        assert!(limit.record_transfer(100, &now).is_ok());
+        assert_eq!(
+            limit.record_transfer(101, &now).unwrap_err(),
+            WhitelistTransferHookError::ExceedsLimit.into()
+        );
+        // A refused transfer does not count towards the window
+        assert_eq!(limit.window_used, 100);
+    }
+
+    #[test]
+    fn test_limit_window_cap() {
+        let mut limit = limit(LimitWindow::Epoch);
+        let now = clock(1, 0);
+
+        limit.record_transfer(100, &now).unwrap();
+        limit.record_transfer(100, &now).unwrap();
+        assert_eq!(
+            limit.record_transfer(51, &now).unwrap_err(),
+            WhitelistTransferHookError::ExceedsLimit.into()
+        );
+        // Exactly filling the window is allowed
+        limit.record_transfer(50, &now).unwrap();
+        assert_eq!(limit.window_used, 250);
+    }
+
+    #[test]
+    fn test_limit_window_rollover() {
+        let mut epoch_limit = limit(LimitWindow::Epoch);
+        epoch_limit.record_transfer(100, &clock(1, 0)).unwrap();
+        epoch_limit.record_transfer(100, &clock(1, 0)).unwrap();
+
+        // A new epoch resets the usage, the timestamp does not matter
+        epoch_limit.record_transfer(100, &clock(2, 0)).unwrap();
+        assert_eq!((epoch_limit.window_id, epoch_limit.window_used), (2, 100));
+
+        let mut day_limit = limit(LimitWindow::Day);
+        let day = 86_400;
+        day_limit.record_transfer(100, &clock(1, 3 * day)).unwrap();
+        day_limit.record_transfer(100, &clock(1, 3 * day + day - 1)).unwrap();
+        assert!(day_limit.record_transfer(100, &clock(1, 4 * day - 1)).is_err());
+
+        // The next day starts at midnight UTC, regardless of the epoch
+        day_limit.record_transfer(100, &clock(1, 4 * day)).unwrap();
+        assert_eq!((day_limit.window_id, day_limit.window_used), (4, 100));
+    }
+
+    #[test]
+    fn test_limit_overflow() {
+        let mut limit = TransferLimit {
+            max_per_transfer: u64::MAX,
+            window_limit: u64::MAX,
+            ..limit(LimitWindow::Epoch)
+        };
+        let now = clock(1, 0);
+
+        limit.record_transfer(u64::MAX, &now).unwrap();
+        assert_eq!(
+            limit.record_transfer(1, &now).unwrap_err(),
+            WhitelistTransferHookError::ArithmeticOverflow.into()
+        );
+        assert_eq!(limit.window_used, u64::MAX);
+    }
+
+    #[test]
+    fn test_error_codes_round_trip() {
+        for &error in WhitelistTransferHookError::ALL {
+            let decoded = WhitelistTransferHookError::from_code(error.into()).unwrap();
+            assert_eq!(decoded.name(), error.name());
+        }
+
+        // The IDL lists one error per `#[msg]` variant of errors.rs, ALL must cover each of them
+        let idl_errors = include_str!("../errors.rs").matches("#[msg(\"").count();
+        assert_eq!(WhitelistTransferHookError::ALL.len(), idl_errors);
+
+        let last = *WhitelistTransferHookError::ALL.last().unwrap();
+        assert_eq!(
+            u32::from(last),
+            anchor_lang::error::ERROR_CODE_OFFSET + WhitelistTransferHookError::ALL.len() as u32 - 1
+        );
+        assert!(WhitelistTransferHookError::from_code(anchor_lang::error::ERROR_CODE_OFFSET - 1).is_none());
+    }
+
+    #[test]
+    fn test_pause_stops_transfers_except_recovery() {
+        let (mut program, admin) = setup();
+        let admin_key = pubkey(&admin.pubkey());
+        let amount = 10u64.pow(DECIMALS as u32);
+        setup_vault(&mut program, &admin, 10 * amount);
+
+        let (alice, alice_token) = funded_user(&mut program, &admin, 3 * amount);
+        let (bob, bob_token) = funded_user(&mut program, &admin, amount);
+        let (recovery, recovery_token) = funded_user(&mut program, &admin, amount);
+        let (alice_key, bob_key, recovery_key) =
+            (pubkey(&alice.pubkey()), pubkey(&bob.pubkey()), pubkey(&recovery.pubkey()));
+
+        let alice_to_bob = hooked_transfer(&alice_token, &bob_token, &alice_key, &alice_key, &bob_key, amount);
+        send(&mut program, std::slice::from_ref(&alice_to_bob), &[&alice]).unwrap();
+        assert_eq!(token_balance(&program, &bob_token), 2 * amount);
+
+        // A Pauser grantee pauses the mint, not just the vault
+        let pauser = funded_keypair(&mut program);
+        send(&mut program, &[grant_role_ix(&admin_key, Role::Pauser, &pubkey(&pauser.pubkey()))], &[&admin]).unwrap();
+        send(&mut program, &[set_paused_ix(&pubkey(&pauser.pubkey()), true)], &[&pauser]).unwrap();
+
+        program.expire_blockhash();
+        assert!(
+            matches!(
+                program_error(send(&mut program, std::slice::from_ref(&alice_to_bob), &[&alice])),
+                Some(WhitelistTransferHookError::TransferPaused)
+            ),
+            "Transfers between whitelisted users should stop while paused"
+        );
+
+        // Without a recovery wallet set, its owner is paused like everyone else
+        let alice_to_recovery =
+            hooked_transfer(&alice_token, &recovery_token, &alice_key, &alice_key, &recovery_key, amount);
+        assert!(
+            matches!(
+                program_error(send(&mut program, std::slice::from_ref(&alice_to_recovery), &[&alice])),
+                Some(WhitelistTransferHookError::TransferPaused)
+            ),
+            "Transfers to a wallet that is not the recovery wallet should stop while paused"
+        );
+
+        // Once set, the recovery wallet may receive and send while paused
+        send(
+            &mut program,
+            &[role_operations_ix(
+                &admin_key,
+                crate::instruction::SetRecoveryWallet { recovery_wallet: Some(recovery_key) },
+            )],
+            &[&admin],
+        )
+        .unwrap();
+        program.expire_blockhash();
+        send(&mut program, std::slice::from_ref(&alice_to_recovery), &[&alice]).unwrap();
+        assert_eq!(token_balance(&program, &recovery_token), 2 * amount);
+
+        let recovery_to_bob =
+            hooked_transfer(&recovery_token, &bob_token, &recovery_key, &recovery_key, &bob_key, amount);
+        send(&mut program, &[recovery_to_bob], &[&recovery]).unwrap();
+        assert_eq!(token_balance(&program, &bob_token), 3 * amount);
+
+        program.expire_blockhash();
+        assert!(
+            matches!(
+                program_error(send(&mut program, std::slice::from_ref(&alice_to_bob), &[&alice])),
+                Some(WhitelistTransferHookError::TransferPaused)
+            ),
+            "Only transfers involving the recovery wallet should go through while paused"
+        );
+
+        // Only the super-admin sets the recovery wallet
+        assert!(
+            matches!(
+                program_error(send(
+                    &mut program,
+                    &[role_operations_ix(
+                        &alice_key,
+                        crate::instruction::SetRecoveryWallet { recovery_wallet: Some(alice_key) },
+                    )],
+                    &[&alice],
+                )),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Only the super-admin should set the recovery wallet"
+        );
+
+        // Unpausing restores transfers
+        send(&mut program, &[set_paused_ix(&pubkey(&pauser.pubkey()), false)], &[&pauser]).unwrap();
+        program.expire_blockhash();
+        send(&mut program, std::slice::from_ref(&alice_to_bob), &[&alice]).unwrap();
+        assert_eq!(token_balance(&program, &bob_token), 4 * amount);
+        assert_eq!(token_balance(&program, &alice_token), 0);
+    }
+
+    #[test]
+    fn test_role_gates() {
+        let (mut program, admin) = setup();
+        let admin_key = pubkey(&admin.pubkey());
+        let outsider = funded_keypair(&mut program);
+        let outsider_key = pubkey(&outsider.pubkey());
+        let amount = 10u64.pow(DECIMALS as u32);
+
+        // initialize_config: only the vault authority becomes the super-admin
+        send(&mut program, &initialize_vault_ixs(&admin_key, 10 * amount), &[&admin]).unwrap();
+        assert!(
+            matches!(
+                program_error(send(&mut program, &[initialize_config_ix(&outsider_key)], &[&outsider])),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Only the vault authority should initialize the config"
+        );
+        send(&mut program, &[initialize_config_ix(&admin_key)], &[&admin]).unwrap();
+        let config = program.get_account(&address(&config_pda())).unwrap();
+        let config = <Config as anchor_lang::AccountDeserialize>::try_deserialize(&mut config.data.as_ref()).unwrap();
+        assert_eq!(config.super_admin, admin_key);
+
+        // grant_role / revoke_role: only the super-admin, not even a grantee
+        let operator = funded_keypair(&mut program);
+        let operator_key = pubkey(&operator.pubkey());
+        assert!(
+            matches!(
+                program_error(send(
+                    &mut program,
+                    &[grant_role_ix(&outsider_key, Role::WhitelistOperator, &outsider_key)],
+                    &[&outsider],
+                )),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Only the super-admin should grant roles"
+        );
+        send(&mut program, &[grant_role_ix(&admin_key, Role::WhitelistOperator, &operator_key)], &[&admin]).unwrap();
+        assert!(
+            matches!(
+                program_error(send(
+                    &mut program,
+                    &[grant_role_ix(&operator_key, Role::WhitelistOperator, &outsider_key)],
+                    &[&operator],
+                )),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "A grantee should not grant roles"
+        );
+        assert!(
+            matches!(
+                program_error(send(
+                    &mut program,
+                    &[revoke_role_ix(&outsider_key, Role::WhitelistOperator, &operator_key)],
+                    &[&outsider],
+                )),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Only the super-admin should revoke roles"
+        );
+
+        // WhitelistOperator gates initialize_whitelist, add_to_whitelist and remove_from_whitelist
+        let user = funded_keypair(&mut program);
+        let user_key = pubkey(&user.pubkey());
+        let add_ix = |operator: &Pubkey| {
+            whitelist_operations_ix(operator, &user_key, crate::instruction::AddToWhitelist { user: user_key })
+        };
+        let remove_ix = |operator: &Pubkey| {
+            whitelist_operations_ix(operator, &user_key, crate::instruction::RemoveFromWhitelist { user: user_key })
+        };
+        assert!(
+            matches!(
+                program_error(send(&mut program, &[initialize_whitelist_ix(&outsider_key, &user_key)], &[&outsider])),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Only a whitelist operator should create entries"
+        );
+        send(&mut program, &[initialize_whitelist_ix(&operator_key, &user_key)], &[&operator]).unwrap();
+        assert!(
+            matches!(
+                program_error(send(&mut program, &[add_ix(&outsider_key)], &[&outsider])),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Only a whitelist operator should add users"
+        );
+        send(&mut program, &[add_ix(&operator_key)], &[&operator]).unwrap();
+        assert!(
+            matches!(
+                program_error(send(&mut program, &[remove_ix(&outsider_key)], &[&outsider])),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Only a whitelist operator should remove users"
+        );
+        send(&mut program, &[remove_ix(&operator_key)], &[&operator]).unwrap();
+
+        // Revoking the role takes the permission away again
+        send(&mut program, &[revoke_role_ix(&admin_key, Role::WhitelistOperator, &operator_key)], &[&admin]).unwrap();
+        program.expire_blockhash();
+        assert!(
+            matches!(
+                program_error(send(&mut program, &[add_ix(&operator_key)], &[&operator])),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "A revoked operator should not add users"
+        );
+
+        // LimitManager gates set_transfer_limit
+        let limit_manager = funded_keypair(&mut program);
+        let limit_manager_key = pubkey(&limit_manager.pubkey());
+        assert!(
+            matches!(
+                program_error(send(
+                    &mut program,
+                    &[set_transfer_limit_ix(&limit_manager_key, &user_key)],
+                    &[&limit_manager],
+                )),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Only a limit manager should set transfer limits"
+        );
+        send(&mut program, &[grant_role_ix(&admin_key, Role::LimitManager, &limit_manager_key)], &[&admin]).unwrap();
+        program.expire_blockhash();
+        send(&mut program, &[set_transfer_limit_ix(&limit_manager_key, &user_key)], &[&limit_manager]).unwrap();
+        assert!(program.get_account(&address(&limit_pda(&user_key))).is_some());
+
+        // Pauser gates set_paused, which stops deposits and withdrawals
+        let (depositor, depositor_token) = funded_user(&mut program, &admin, amount);
+        let depositor_key = pubkey(&depositor.pubkey());
+        let pauser = funded_keypair(&mut program);
+        let pauser_key = pubkey(&pauser.pubkey());
+        assert!(
+            matches!(
+                program_error(send(&mut program, &[set_paused_ix(&pauser_key, true)], &[&pauser])),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Only a pauser should pause the vault"
+        );
+        send(&mut program, &[grant_role_ix(&admin_key, Role::Pauser, &pauser_key)], &[&admin]).unwrap();
+        program.expire_blockhash();
+        send(&mut program, &[set_paused_ix(&pauser_key, true)], &[&pauser]).unwrap();
+
+        assert!(
+            matches!(
+                program_error(send(
+                    &mut program,
+                    &[deposit_ix(&depositor_key, &depositor_token, amount)],
+                    &[&depositor],
+                )),
+                Some(WhitelistTransferHookError::VaultPaused)
+            ),
+            "Deposits should stop while paused"
+        );
+        assert!(
+            matches!(
+                program_error(send(&mut program, &[withdraw_ix(&depositor_key, amount)], &[&depositor])),
+                Some(WhitelistTransferHookError::VaultPaused)
+            ),
+            "Withdrawals should stop while paused"
+        );
+
+        program.expire_blockhash();
+        send(&mut program, &[set_paused_ix(&pauser_key, false)], &[&pauser]).unwrap();
+        send(&mut program, &[deposit_ix(&depositor_key, &depositor_token, amount)], &[&depositor]).unwrap();
+    }
+
+    #[test]
+    fn test_vault_withdrawal_under_delegate_policies() {
+        let (mut program, admin) = setup();
+        let admin_key = pubkey(&admin.pubkey());
+        let amount = 10u64.pow(DECIMALS as u32);
+        setup_vault(&mut program, &admin, 10 * amount);
+
+        let (alice, alice_token) = funded_user(&mut program, &admin, amount);
+        let alice_key = pubkey(&alice.pubkey());
+
+        // Carol has an entry, so the hook resolves it, but is not whitelisted
+        let carol = funded_keypair(&mut program);
+        let carol_key = pubkey(&carol.pubkey());
+        let carol_token = CreateAssociatedTokenAccount::new(&mut program, &admin, &address(&mint_pda()))
+            .owner(&carol.pubkey())
+            .token_program_id(&address(&TOKEN_2022_PROGRAM_ID))
+            .send()
+            .unwrap();
+        let carol_token = pubkey(&carol_token);
+        send(&mut program, &[initialize_whitelist_ix(&admin_key, &carol_key)], &[&admin]).unwrap();
+
+        // Alice withdraws as the vault's delegate, the vault state owning the source account
+        let withdraw_to = |destination: &Pubkey, destination_owner: &Pubkey| {
+            [
+                deposit_ix(&alice_key, &alice_token, amount),
+                withdraw_ix(&alice_key, amount),
+                hooked_transfer(&vault_pda(), destination, &vault_state_pda(), &alice_key, destination_owner, amount),
+            ]
+        };
+
+        let config = program.get_account(&address(&config_pda())).unwrap();
+        let config = <Config as anchor_lang::AccountDeserialize>::try_deserialize(&mut config.data.as_ref()).unwrap();
+        assert_eq!(config.delegate_policy, DelegatePolicy::Both);
+
+        // Only `Delegate` lets the withdrawal out to a wallet that is not whitelisted. The vault
+        // state has no entry, so under `Owner` and the default `Both` the source side fails and
+        // withdrawals to Alice's own account only pass because she, the destination, is whitelisted.
+        let mut alice_balance = amount;
+        let mut carol_balance = 0;
+        for (policy, reaches_carol) in [
+            (DelegatePolicy::Both, false),
+            (DelegatePolicy::Delegate, true),
+            (DelegatePolicy::Owner, false),
+            (DelegatePolicy::Both, false),
+        ] {
+            program.expire_blockhash();
+            send(
+                &mut program,
+                &[role_operations_ix(&admin_key, crate::instruction::SetDelegatePolicy { delegate_policy: policy })],
+                &[&admin],
+            )
+            .unwrap();
+
+            send(&mut program, &withdraw_to(&alice_token, &alice_key), &[&alice]).unwrap();
+            alice_balance += amount;
+            assert_eq!(token_balance(&program, &alice_token), alice_balance, "{policy:?}");
+
+            let result = send(&mut program, &withdraw_to(&carol_token, &carol_key), &[&alice]);
+            if reaches_carol {
+                result.unwrap();
+                carol_balance += amount;
+            } else {
+                assert!(
+                    matches!(program_error(result), Some(WhitelistTransferHookError::NotWhitelisted)),
+                    "Withdrawal to a wallet that is not whitelisted should fail under {policy:?}"
+                );
+            }
+            assert_eq!(token_balance(&program, &carol_token), carol_balance, "{policy:?}");
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum EscrowError {
+    #[msg("Escrow is time-locked. Must wait at least 5 days (432000 seconds) after creation.")]
+    EscrowTimeLocked,
+    #[msg("Escrow is not listed in the market for this mint pair")]
+    OfferNotListed,
+    #[msg("Market page already holds the maximum number of offers, use another page")]
+    MarketPageFull,
+    #[msg("Tolerance band must not exceed 10000 basis points")]
+    InvalidTolerance,
+    #[msg("Price feed account does not match the escrow's price band or cannot be read")]
+    InvalidPriceFeed,
+    #[msg("Maximum staleness must not be negative")]
+    InvalidStaleness,
+    #[msg("Price feed data is older than the allowed staleness")]
+    StalePriceFeed,
+    #[msg("Offer price is outside the tolerance band around the feed price")]
+    PriceOutOfBand,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Mint is not a non-fungible token: expected 0 decimals, a supply of 1 and no mint authority")]
+    NotNonFungible,
+    #[msg("Mint is not a member of the expected collection")]
+    CollectionMismatch,
+    #[msg("Signer is not the maker of this escrow")]
+    Unauthorized,
+    #[msg("Escrow already uses the current layout")]
+    AlreadyMigrated,
+    #[msg("Price band or NFT listing accounts do not match what is attached to the escrow")]
+    AttachmentMismatch,
+}
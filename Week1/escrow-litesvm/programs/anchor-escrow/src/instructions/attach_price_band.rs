@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::EscrowError;
+use crate::state::{Escrow, PriceBand, PriceFeed};
+
+#[derive(Accounts)]
+pub struct AttachPriceBand<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(
+        mut,
+        has_one = maker,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    /// CHECK: Price feed chosen by the maker, its data must decode as a `PriceFeed`.
+    /// Take only accepts it while it is still owned by the same program.
+    #[account(
+        constraint = oracle.owner != &System::id() @ EscrowError::InvalidPriceFeed,
+    )]
+    pub oracle: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"price_band", escrow.key().as_ref()],
+        bump,
+        space = 8 + PriceBand::INIT_SPACE,
+    )]
+    pub price_band: Account<'info, PriceBand>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AttachPriceBand<'info> {
+    pub fn attach_price_band(
+        &mut self,
+        tolerance_bps: u16,
+        max_staleness: i64,
+        bumps: &AttachPriceBandBumps,
+    ) -> Result<()> {
+        require!(
+            tolerance_bps <= PriceBand::MAX_TOLERANCE_BPS,
+            EscrowError::InvalidTolerance
+        );
+        require!(max_staleness >= 0, EscrowError::InvalidStaleness);
+
+        // Reject feeds take would not be able to read
+        PriceFeed::try_from_slice(&self.oracle.try_borrow_data()?)?;
+
+        self.price_band.set_inner(PriceBand {
+            escrow: self.escrow.key(),
+            oracle: self.oracle.key(),
+            oracle_program: *self.oracle.owner,
+            tolerance_bps,
+            max_staleness,
+            bump: bumps.price_band,
+        });
+        self.escrow.has_price_band = true;
+
+        Ok(())
+    }
+}
@@ -1,10 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked}};
 
-use crate::state::Escrow;
+use crate::errors::EscrowError;
+use crate::state::{Escrow, Market, TraderStats};
 
 #[derive(Accounts)]
-#[instruction(seed: u64)]
+#[instruction(seed: u64, page: u32)]
 pub struct Make<'info> {
     #[account(mut)]
     pub maker: Signer<'info>,
@@ -14,6 +15,7 @@ pub struct Make<'info> {
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = maker,
+        associated_token::token_program = token_program,
     )]
     pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
     #[account(
@@ -29,15 +31,32 @@ pub struct Make<'info> {
         payer = maker,
         associated_token::mint = mint_a,
         associated_token::authority = escrow,
+        associated_token::token_program = token_program,
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = maker,
+        seeds = [b"market", mint_a.key().as_ref(), mint_b.key().as_ref(), page.to_le_bytes().as_ref()],
+        bump,
+        space = Market::SPACE,
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        init_if_needed,
+        payer = maker,
+        seeds = [b"stats", maker.key().as_ref()],
+        bump,
+        space = TraderStats::SPACE,
+    )]
+    pub maker_stats: Account<'info, TraderStats>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Make<'info> {
-    pub fn init_escrow(&mut self, seed: u64, receive: u64, bumps: &MakeBumps) -> Result<()> {
+    pub fn init_escrow(&mut self, seed: u64, page: u32, receive: u64, bumps: &MakeBumps) -> Result<()> {
         self.escrow.set_inner(Escrow {
             seed,
             maker: self.maker.key(),
@@ -46,11 +65,44 @@ impl<'info> Make<'info> {
             receive,
             bump: bumps.escrow,
             start_time: Clock::get()?.unix_timestamp, // Store the current timestamp
+            version: Escrow::CURRENT_VERSION,
+            has_price_band: false,
+            has_nft_listing: false,
+            market_page: page,
+            reserved: [0; 58],
         });
 
         Ok(())
     }
 
+    pub fn list_offer(&mut self, bumps: &MakeBumps) -> Result<()> {
+        // First offer on this page, record the pair on the freshly created market page
+        if self.market.offers.is_empty() {
+            self.market.mint_a = self.mint_a.key();
+            self.market.mint_b = self.mint_b.key();
+            self.market.page = self.escrow.market_page;
+            self.market.bump = bumps.market;
+        }
+
+        require!(self.market.offers.len() < Market::MAX_OFFERS, EscrowError::MarketPageFull);
+        self.market.offers.push(self.escrow.key());
+
+        Ok(())
+    }
+
+    pub fn record_offer(&mut self, bumps: &MakeBumps) -> Result<()> {
+        // First activity for this wallet, bind the freshly created stats account to it
+        if self.maker_stats.trader == Pubkey::default() {
+            self.maker_stats.trader = self.maker.key();
+            self.maker_stats.bump = bumps.maker_stats;
+        }
+
+        self.maker_stats.offers_created = self.maker_stats.offers_created.saturating_add(1);
+        self.maker_stats.last_active = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+
     pub fn deposit(&mut self, deposit: u64) -> Result<()> {
         let cpi_program = self.token_program.to_account_info();
 
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::instructions::make::*;
+use crate::state::NftListing;
+
+#[derive(Accounts)]
+#[instruction(seed: u64, page: u32)]
+pub struct MakeNft<'info> {
+    pub make: Make<'info>,
+    #[account(
+        init,
+        payer = make.maker,
+        seeds = [b"nft_listing", make.escrow.key().as_ref()],
+        bump,
+        space = 8 + NftListing::INIT_SPACE,
+    )]
+    pub nft_listing: Account<'info, NftListing>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeNft<'info> {
+    pub fn list_nft(&mut self, collection: Option<Pubkey>, bumps: &MakeNftBumps) -> Result<()> {
+        NftListing::verify(
+            &self.make.mint_a.to_account_info(),
+            &self.make.mint_a,
+            collection,
+        )?;
+
+        self.nft_listing.set_inner(NftListing {
+            escrow: self.make.escrow.key(),
+            collection,
+            bump: bumps.nft_listing,
+        });
+        self.make.escrow.has_nft_listing = true;
+
+        Ok(())
+    }
+}
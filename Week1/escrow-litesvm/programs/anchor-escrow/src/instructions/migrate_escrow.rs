@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::EscrowError;
+use crate::state::{Escrow, Market};
+use crate::utils::grow_account;
+
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct MigrateEscrow<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    /// CHECK: Escrow in any layout version, decoded and validated in `migrate_escrow`
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub escrow: UncheckedAccount<'info>,
+    /// CHECK: Only its address seeds the market, checked against the escrow in `migrate_escrow`
+    pub mint_a: UncheckedAccount<'info>,
+    /// CHECK: Only its address seeds the market, checked against the escrow in `migrate_escrow`
+    pub mint_b: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = maker,
+        seeds = [b"market", mint_a.key().as_ref(), mint_b.key().as_ref(), page.to_le_bytes().as_ref()],
+        bump,
+        space = Market::SPACE,
+    )]
+    pub market: Account<'info, Market>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateEscrow<'info> {
+    pub fn migrate_escrow(&mut self, page: u32, bumps: &MigrateEscrowBumps) -> Result<()> {
+        let escrow = Escrow::decode(&self.escrow.try_borrow_data()?)?;
+
+        require_keys_eq!(escrow.maker, self.maker.key(), EscrowError::Unauthorized);
+        let expected = Pubkey::create_program_address(
+            &[
+                b"escrow",
+                escrow.maker.as_ref(),
+                &escrow.seed.to_le_bytes()[..],
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| error!(ErrorCode::ConstraintSeeds))?;
+        require_keys_eq!(expected, self.escrow.key(), ErrorCode::ConstraintSeeds);
+        require_keys_eq!(escrow.mint_a, self.mint_a.key(), ErrorCode::ConstraintSeeds);
+        require_keys_eq!(escrow.mint_b, self.mint_b.key(), ErrorCode::ConstraintSeeds);
+
+        // Current escrows stay on the page they were opened on
+        if escrow.version == Escrow::CURRENT_VERSION {
+            require_eq!(escrow.market_page, page, ErrorCode::ConstraintSeeds);
+        }
+
+        // Escrows opened before markets existed are listed here, take requires it
+        let listed = self.market.offers.contains(&self.escrow.key());
+        require!(
+            escrow.version < Escrow::CURRENT_VERSION || !listed,
+            EscrowError::AlreadyMigrated
+        );
+
+        if !listed {
+            self.list_offer(page, bumps)?;
+        }
+
+        // Extend the account to the current layout, the maker pays the extra rent
+        grow_account(
+            &self.escrow.to_account_info(),
+            &self.maker.to_account_info(),
+            &self.system_program.to_account_info(),
+            8 + Escrow::INIT_SPACE,
+        )?;
+
+        let migrated = Escrow {
+            version: Escrow::CURRENT_VERSION,
+            market_page: page,
+            ..escrow
+        };
+        migrated.try_serialize(&mut &mut self.escrow.try_borrow_mut_data()?[..])?;
+
+        msg!("Escrow {} migrated to version {}", self.escrow.key(), Escrow::CURRENT_VERSION);
+
+        Ok(())
+    }
+
+    fn list_offer(&mut self, page: u32, bumps: &MigrateEscrowBumps) -> Result<()> {
+        // First offer on this page, record the pair on the freshly created market page
+        if self.market.offers.is_empty() {
+            self.market.mint_a = self.mint_a.key();
+            self.market.mint_b = self.mint_b.key();
+            self.market.page = page;
+            self.market.bump = bumps.market;
+        }
+
+        require!(self.market.offers.len() < Market::MAX_OFFERS, EscrowError::MarketPageFull);
+        self.market.offers.push(self.escrow.key());
+
+        Ok(())
+    }
+}
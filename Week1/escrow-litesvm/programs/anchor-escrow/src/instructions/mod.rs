@@ -1,7 +1,13 @@
+pub mod attach_price_band;
 pub mod make;
+pub mod make_nft;
+pub mod migrate_escrow;
 pub mod refund;
 pub mod take;
 
+pub use attach_price_band::*;
 pub use make::*;
+pub use make_nft::*;
+pub use migrate_escrow::*;
 pub use refund::*;
-pub use take::*;
\ No newline at end of file
+pub use take::*;
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked, CloseAccount, close_account};
 
-use crate::state::Escrow;
+use crate::errors::EscrowError;
+use crate::state::{Escrow, Market, NftListing, PriceBand, TraderStats};
 
 #[derive(Accounts)]
 pub struct Refund<'info> {
@@ -12,6 +13,7 @@ pub struct Refund<'info> {
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = maker,
+        associated_token::token_program = token_program,
     )]
     maker_ata_a: InterfaceAccount<'info, TokenAccount>,
     #[account(
@@ -21,14 +23,47 @@ pub struct Refund<'info> {
         has_one = maker,
         seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         bump = escrow.bump,
+        constraint = escrow.has_price_band == price_band.is_some() @ EscrowError::AttachmentMismatch,
+        constraint = escrow.has_nft_listing == nft_listing.is_some() @ EscrowError::AttachmentMismatch,
     )]
     pub escrow: Account<'info, Escrow>,
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = escrow,
+        associated_token::token_program = token_program,
     )]
     vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"market", mint_a.key().as_ref(), escrow.mint_b.as_ref(), escrow.market_page.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    market: Account<'info, Market>,
+    #[account(
+        init_if_needed,
+        payer = maker,
+        seeds = [b"stats", maker.key().as_ref()],
+        bump,
+        space = TraderStats::SPACE,
+    )]
+    maker_stats: Account<'info, TraderStats>,
+    // Passed exactly when the maker attached a band, closed along with the escrow
+    #[account(
+        mut,
+        close = maker,
+        seeds = [b"price_band", escrow.key().as_ref()],
+        bump = price_band.bump,
+    )]
+    price_band: Option<Account<'info, PriceBand>>,
+    // Passed exactly when the escrow was opened through make_nft, closed along with the escrow
+    #[account(
+        mut,
+        close = maker,
+        seeds = [b"nft_listing", escrow.key().as_ref()],
+        bump = nft_listing.bump,
+    )]
+    nft_listing: Option<Account<'info, NftListing>>,
     token_program: Interface<'info, TokenInterface>,
     system_program: Program<'info, System>,
 }
@@ -69,4 +104,24 @@ impl<'info> Refund<'info> {
         
         Ok(())
     }
+
+    pub fn delist_offer(&mut self) -> Result<()> {
+        // Not required to be listed, the maker can always get their deposit back
+        self.market.remove_offer(&self.escrow.key());
+
+        Ok(())
+    }
+
+    pub fn record_cancel(&mut self, bumps: &RefundBumps) -> Result<()> {
+        // Escrows opened before stats existed have no account yet, bind the fresh one to the maker
+        if self.maker_stats.trader == Pubkey::default() {
+            self.maker_stats.trader = self.maker.key();
+            self.maker_stats.bump = bumps.maker_stats;
+        }
+
+        self.maker_stats.offers_cancelled = self.maker_stats.offers_cancelled.saturating_add(1);
+        self.maker_stats.last_active = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
 }
\ No newline at end of file
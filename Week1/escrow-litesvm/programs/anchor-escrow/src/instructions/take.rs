@@ -1,14 +1,8 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{associated_token::AssociatedToken, token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked, CloseAccount, close_account}};
 
-use crate::state::Escrow;
-
-
-#[error_code]
-pub enum EscrowError {
-    #[msg("Escrow is time-locked. Must wait at least 5 days (432000 seconds) after creation.")]
-    EscrowTimeLocked,
-}
+use crate::errors::EscrowError;
+use crate::state::{Escrow, Market, NftListing, PriceBand, PriceFeed, TraderStats};
 
 //Create context
 #[derive(Accounts)]
@@ -24,12 +18,14 @@ pub struct Take<'info> {
         payer = taker,
         associated_token::mint = mint_a,
         associated_token::authority = taker,
+        associated_token::token_program = token_program,
     )]
     pub taker_ata_a: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         associated_token::mint = mint_b,
         associated_token::authority = taker,
+        associated_token::token_program = token_program,
     )]
     pub taker_ata_b: InterfaceAccount<'info, TokenAccount>,
     #[account(
@@ -37,6 +33,7 @@ pub struct Take<'info> {
         payer = taker,
         associated_token::mint = mint_b,
         associated_token::authority = maker,
+        associated_token::token_program = token_program,
     )]
     pub maker_ata_b: InterfaceAccount<'info, TokenAccount>,
     #[account(
@@ -47,14 +44,59 @@ pub struct Take<'info> {
         has_one = mint_b,
         seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         bump = escrow.bump,
+        constraint = escrow.has_price_band == price_band.is_some() @ EscrowError::AttachmentMismatch,
+        constraint = escrow.has_nft_listing == nft_listing.is_some() @ EscrowError::AttachmentMismatch,
     )]
     pub escrow: Account<'info, Escrow>,
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = escrow,
+        associated_token::token_program = token_program,
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"market", mint_a.key().as_ref(), mint_b.key().as_ref(), escrow.market_page.to_le_bytes().as_ref()],
+        bump = market.bump,
+        constraint = market.offers.contains(&escrow.key()) @ EscrowError::OfferNotListed,
+    )]
+    pub market: Account<'info, Market>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        seeds = [b"stats", maker.key().as_ref()],
+        bump,
+        space = TraderStats::SPACE,
+    )]
+    pub maker_stats: Account<'info, TraderStats>,
+    // Passed exactly when the maker attached a band, closed along with the escrow
+    #[account(
+        mut,
+        close = maker,
+        seeds = [b"price_band", escrow.key().as_ref()],
+        bump = price_band.bump,
+    )]
+    pub price_band: Option<Account<'info, PriceBand>>,
+    // Passed exactly when the escrow was opened through make_nft, closed along with the escrow
+    #[account(
+        mut,
+        close = maker,
+        seeds = [b"nft_listing", escrow.key().as_ref()],
+        bump = nft_listing.bump,
+    )]
+    pub nft_listing: Option<Account<'info, NftListing>>,
+    /// CHECK: Price feed named by the price band, validated against it in `check_price_band`
+    pub oracle: Option<UncheckedAccount<'info>>,
+    // Same account as `maker_stats` when makers take their own offer, see `record_fill`
+    #[account(
+        init_if_needed,
+        payer = taker,
+        seeds = [b"stats", taker.key().as_ref()],
+        bump,
+        space = TraderStats::SPACE,
+    )]
+    pub taker_stats: Account<'info, TraderStats>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
@@ -64,6 +106,43 @@ pub struct Take<'info> {
 //Transfer tokens from vault to taker
 //Close vault account
 impl<'info> Take<'info> {
+    pub fn check_price_band(&mut self) -> Result<()> {
+        // No price band attached, the offer settles at its fixed terms
+        let Some(price_band) = &self.price_band else {
+            return Ok(());
+        };
+        let oracle = self
+            .oracle
+            .as_ref()
+            .ok_or(EscrowError::InvalidPriceFeed)?;
+        require_keys_eq!(oracle.key(), price_band.oracle, EscrowError::InvalidPriceFeed);
+        require_keys_eq!(*oracle.owner, price_band.oracle_program, EscrowError::InvalidPriceFeed);
+
+        let feed = PriceFeed::try_from_slice(&oracle.try_borrow_data()?)?;
+
+        price_band.check(
+            &feed,
+            Clock::get()?.unix_timestamp,
+            self.vault.amount,
+            self.mint_a.decimals,
+            self.escrow.receive,
+            self.mint_b.decimals,
+        )
+    }
+
+    pub fn check_nft_listing(&mut self) -> Result<()> {
+        // Not opened through make_nft, nothing to re-verify
+        let Some(nft_listing) = &self.nft_listing else {
+            return Ok(());
+        };
+
+        NftListing::verify(
+            &self.mint_a.to_account_info(),
+            &self.mint_a,
+            nft_listing.collection,
+        )
+    }
+
     pub fn deposit(&mut self) -> Result<()> {
         // Check if 5 days (432000 seconds) have passed since escrow creation
         let current_time = Clock::get()?.unix_timestamp;
@@ -122,4 +201,49 @@ impl<'info> Take<'info> {
 
         close_account(cpi_context)
     }
+
+    pub fn delist_offer(&mut self) -> Result<()> {
+        self.market.remove_offer(&self.escrow.key());
+
+        Ok(())
+    }
+
+    pub fn record_fill(&mut self, deposit: u64, bumps: &TakeBumps) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        // Escrows opened before stats existed have no maker account yet, bind the fresh one
+        if self.maker_stats.trader == Pubkey::default() {
+            self.maker_stats.trader = self.maker.key();
+            self.maker_stats.bump = bumps.maker_stats;
+        }
+
+        // First activity for this wallet, bind the freshly created stats account to it
+        if self.taker_stats.trader == Pubkey::default() {
+            self.taker_stats.trader = self.taker.key();
+            self.taker_stats.bump = bumps.taker_stats;
+        }
+
+        self.maker_stats.offers_filled = self.maker_stats.offers_filled.saturating_add(1);
+        self.maker_stats.record_volume(self.mint_a.key(), deposit);
+        self.maker_stats.record_volume(self.mint_b.key(), self.escrow.receive);
+        self.maker_stats.last_active = now;
+
+        // A maker taking their own offer has one stats account, loaded twice. Update a single
+        // copy and mirror it, whichever copy is written back last then holds both sides. The
+        // volume only changed hands once, it is counted once.
+        if self.maker.key() == self.taker.key() {
+            self.maker_stats.offers_taken = self.maker_stats.offers_taken.saturating_add(1);
+            let stats = (*self.maker_stats).clone();
+            self.taker_stats.set_inner(stats);
+
+            return Ok(());
+        }
+
+        self.taker_stats.offers_taken = self.taker_stats.offers_taken.saturating_add(1);
+        self.taker_stats.record_volume(self.mint_a.key(), deposit);
+        self.taker_stats.record_volume(self.mint_b.key(), self.escrow.receive);
+        self.taker_stats.last_active = now;
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -3,8 +3,11 @@
 
 use anchor_lang::prelude::*;
 
-mod state;
+// Account layouts and `Escrow::decode` are public so off-chain clients can read escrows in any version
+pub mod state;
 mod instructions;
+mod errors;
+mod utils;
 #[cfg(test)]
 mod tests;
 
@@ -16,17 +19,52 @@ declare_id!("FircrADQ2wgGuvpm8qneNCfKM7o5zoHTWnDQxngpTQ3J");
 pub mod anchor_escrow {
     use super::*;
 
-    pub fn make(ctx: Context<Make>, seed: u64, deposit: u64, receive: u64) -> Result<()> {
-        ctx.accounts.init_escrow(seed, receive, &ctx.bumps)?;
+    pub fn make(ctx: Context<Make>, seed: u64, page: u32, deposit: u64, receive: u64) -> Result<()> {
+        ctx.accounts.init_escrow(seed, page, receive, &ctx.bumps)?;
+        ctx.accounts.list_offer(&ctx.bumps)?;
+        ctx.accounts.record_offer(&ctx.bumps)?;
         ctx.accounts.deposit(deposit)
     }
 
+    pub fn make_nft(
+        ctx: Context<MakeNft>,
+        seed: u64,
+        page: u32,
+        receive: u64,
+        collection: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.make.init_escrow(seed, page, receive, &ctx.bumps.make)?;
+        ctx.accounts.list_nft(collection, &ctx.bumps)?;
+        ctx.accounts.make.list_offer(&ctx.bumps.make)?;
+        ctx.accounts.make.record_offer(&ctx.bumps.make)?;
+        ctx.accounts.make.deposit(1)
+    }
+
+    pub fn attach_price_band(
+        ctx: Context<AttachPriceBand>,
+        tolerance_bps: u16,
+        max_staleness: i64,
+    ) -> Result<()> {
+        ctx.accounts.attach_price_band(tolerance_bps, max_staleness, &ctx.bumps)
+    }
+
+    pub fn migrate_escrow(ctx: Context<MigrateEscrow>, page: u32) -> Result<()> {
+        ctx.accounts.migrate_escrow(page, &ctx.bumps)
+    }
+
     pub fn refund(ctx: Context<Refund>) -> Result<()> {
-        ctx.accounts.refund_and_close_vault()
+        ctx.accounts.refund_and_close_vault()?;
+        ctx.accounts.delist_offer()?;
+        ctx.accounts.record_cancel(&ctx.bumps)
     }
 
     pub fn take(ctx: Context<Take>) -> Result<()> {
+        ctx.accounts.check_price_band()?;
+        ctx.accounts.check_nft_listing()?;
         ctx.accounts.deposit()?;
-        ctx.accounts.withdraw_and_close_vault()
+        let deposit = ctx.accounts.vault.amount;
+        ctx.accounts.withdraw_and_close_vault()?;
+        ctx.accounts.delist_offer()?;
+        ctx.accounts.record_fill(deposit, &ctx.bumps)
     }
 }
\ No newline at end of file
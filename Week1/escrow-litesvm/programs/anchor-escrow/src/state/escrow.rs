@@ -10,4 +10,60 @@ pub struct Escrow {
     pub receive: u64,
     pub bump: u8,
     pub start_time: i64, // Timestamp when the escrow was created
-}
\ No newline at end of file
+    pub version: u8,     // Layout version, 0 for escrows created before versioning
+    pub has_price_band: bool,  // Set by attach_price_band, take must then pass the band
+    pub has_nft_listing: bool, // Set by make_nft, take must then pass the listing
+    pub market_page: u32,      // Page of the pair's market the escrow is listed on
+    pub reserved: [u8; 58], // Zeroed padding for future fields
+}
+
+/// Escrow layout used before the version byte was introduced
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, Debug)]
+pub struct LegacyEscrow {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub receive: u64,
+    pub bump: u8,
+    pub start_time: i64,
+}
+
+impl Escrow {
+    pub const CURRENT_VERSION: u8 = 1;
+
+    /// Decodes an escrow account in either the legacy or the current layout
+    pub fn decode(data: &[u8]) -> Result<Escrow> {
+        if data.len() < Escrow::DISCRIMINATOR.len() || &data[..8] != Escrow::DISCRIMINATOR {
+            return err!(ErrorCode::AccountDiscriminatorMismatch);
+        }
+
+        if data.len() >= 8 + Escrow::INIT_SPACE {
+            return Escrow::try_deserialize(&mut &data[..]);
+        }
+
+        let legacy = LegacyEscrow::deserialize(&mut &data[8..])
+            .map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))?;
+
+        Ok(Escrow::from(legacy))
+    }
+}
+
+impl From<LegacyEscrow> for Escrow {
+    fn from(legacy: LegacyEscrow) -> Self {
+        Escrow {
+            seed: legacy.seed,
+            maker: legacy.maker,
+            mint_a: legacy.mint_a,
+            mint_b: legacy.mint_b,
+            receive: legacy.receive,
+            bump: legacy.bump,
+            start_time: legacy.start_time,
+            version: 0,
+            has_price_band: false,
+            has_nft_listing: false,
+            market_page: 0,
+            reserved: [0; 58],
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// One page of the open offers of a mint pair. Pages hold at most `MAX_OFFERS` escrows so that
+/// reading one stays cheap however many offers the pair has, makers pick a page with room.
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Market {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub page: u32,
+    pub bump: u8,
+    #[max_len(32)]
+    pub offers: Vec<Pubkey>, // Open escrow addresses, pushed on make and removed on take/refund
+}
+
+impl Market {
+    pub const MAX_OFFERS: usize = 32;
+    pub const SPACE: usize = 8 + Market::INIT_SPACE;
+
+    /// Drops a filled or refunded escrow from the open offers
+    pub fn remove_offer(&mut self, escrow: &Pubkey) {
+        if let Some(index) = self.offers.iter().position(|offer| offer == escrow) {
+            self.offers.swap_remove(index);
+        }
+    }
+}
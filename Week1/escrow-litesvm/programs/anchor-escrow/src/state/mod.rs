@@ -1,3 +1,11 @@
 pub mod escrow;
+pub mod market;
+pub mod nft_listing;
+pub mod price_band;
+pub mod trader_stats;
 
-pub use escrow::*;
\ No newline at end of file
+pub use escrow::*;
+pub use market::*;
+pub use nft_listing::*;
+pub use price_band::*;
+pub use trader_stats::*;
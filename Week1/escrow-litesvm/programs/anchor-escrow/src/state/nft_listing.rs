@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::extension::group_member_pointer::GroupMemberPointer,
+    token_interface::{get_mint_extension_data, Mint},
+};
+use spl_token_group_interface::state::TokenGroupMember;
+
+use crate::errors::EscrowError;
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct NftListing {
+    pub escrow: Pubkey,
+    pub collection: Option<Pubkey>, // Token-2022 group the escrowed NFT must belong to
+    pub bump: u8,
+}
+
+impl NftListing {
+    /// Checks that `mint` is a non-fungible token and, when `collection` is set, a member of that group
+    pub fn verify(
+        mint_info: &AccountInfo,
+        mint: &Mint,
+        collection: Option<Pubkey>,
+    ) -> Result<()> {
+        // Exactly one indivisible token that can never be minted again
+        require!(
+            mint.decimals == 0 && mint.supply == 1 && mint.mint_authority.is_none(),
+            EscrowError::NotNonFungible
+        );
+
+        let Some(collection) = collection else {
+            return Ok(());
+        };
+
+        // Membership is read from the mint itself, so its group member pointer must point back at it
+        let pointer = get_mint_extension_data::<GroupMemberPointer>(mint_info)
+            .map_err(|_| error!(EscrowError::CollectionMismatch))?;
+        require!(
+            Option::<Pubkey>::from(pointer.member_address) == Some(mint_info.key()),
+            EscrowError::CollectionMismatch
+        );
+
+        let member = get_mint_extension_data::<TokenGroupMember>(mint_info)
+            .map_err(|_| error!(EscrowError::CollectionMismatch))?;
+        require_keys_eq!(member.group, collection, EscrowError::CollectionMismatch);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::EscrowError;
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct PriceBand {
+    pub escrow: Pubkey,
+    pub oracle: Pubkey,         // Price feed account read by take
+    pub oracle_program: Pubkey, // Program owning the feed when the band was attached
+    pub tolerance_bps: u16,     // Allowed distance between the offer price and the feed price
+    pub max_staleness: i64,     // Maximum age of the feed price in seconds
+    pub bump: u8,
+}
+
+/// Price feed layout read by `take`, quoting one whole mint A token in whole mint B tokens
+/// as `price * 10^expo`. Mock oracles in tests store this borsh encoding as raw account data.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PriceFeed {
+    pub price: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl PriceFeed {
+    pub fn try_from_slice(data: &[u8]) -> Result<Self> {
+        PriceFeed::deserialize(&mut &data[..]).map_err(|_| error!(EscrowError::InvalidPriceFeed))
+    }
+}
+
+impl PriceBand {
+    pub const MAX_TOLERANCE_BPS: u16 = 10_000;
+
+    /// Checks that the offer price `receive / deposit` sits within the band around a fresh feed price
+    pub fn check(
+        &self,
+        feed: &PriceFeed,
+        now: i64,
+        deposit: u64,
+        decimals_a: u8,
+        receive: u64,
+        decimals_b: u8,
+    ) -> Result<()> {
+        require!(self.max_staleness >= 0, EscrowError::InvalidStaleness);
+        // A publish time ahead of the clock would read as fresh for longer than the band allows
+        require!(feed.publish_time <= now, EscrowError::InvalidPriceFeed);
+        require!(
+            now - feed.publish_time <= self.max_staleness,
+            EscrowError::StalePriceFeed
+        );
+        require!(feed.price > 0 && deposit > 0, EscrowError::InvalidPriceFeed);
+
+        // Cross-multiply both prices onto a common integer scale:
+        // receive * 10^decimals_a / (deposit * 10^decimals_b) against price * 10^expo
+        let pow = |exp: u32| 10u128.checked_pow(exp).ok_or(EscrowError::MathOverflow);
+        let (offer_exp, feed_exp) = if feed.expo < 0 {
+            (feed.expo.unsigned_abs(), 0)
+        } else {
+            (0, feed.expo.unsigned_abs())
+        };
+
+        let offer_scale = pow(decimals_a as u32 + offer_exp)?;
+        let feed_scale = pow(decimals_b as u32 + feed_exp)?;
+
+        let offer = (receive as u128)
+            .checked_mul(offer_scale)
+            .and_then(|v| v.checked_mul(Self::MAX_TOLERANCE_BPS as u128))
+            .ok_or(EscrowError::MathOverflow)?;
+        let reference = (feed.price as u128)
+            .checked_mul(deposit as u128)
+            .and_then(|v| v.checked_mul(feed_scale))
+            .ok_or(EscrowError::MathOverflow)?;
+
+        let lower = reference
+            .checked_mul((Self::MAX_TOLERANCE_BPS - self.tolerance_bps) as u128)
+            .ok_or(EscrowError::MathOverflow)?;
+        let upper = reference
+            .checked_mul((Self::MAX_TOLERANCE_BPS + self.tolerance_bps) as u128)
+            .ok_or(EscrowError::MathOverflow)?;
+
+        require!(offer >= lower && offer <= upper, EscrowError::PriceOutOfBand);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct TraderStats {
+    pub trader: Pubkey,
+    pub offers_created: u64,   // Escrows opened as maker
+    pub offers_filled: u64,    // Escrows opened as maker that were taken
+    pub offers_cancelled: u64, // Escrows opened as maker that were refunded
+    pub offers_taken: u64,     // Escrows filled as taker
+    pub last_active: i64,      // Timestamp of the last make, take or refund
+    pub bump: u8,
+    #[max_len(16)]
+    pub volumes: Vec<MintVolume>, // Filled volume per mint, for the first MAX_MINTS mints traded
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, Debug, PartialEq)]
+pub struct MintVolume {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+impl TraderStats {
+    pub const MAX_MINTS: usize = 16;
+    pub const SPACE: usize = 8 + TraderStats::INIT_SPACE;
+
+    /// Adds `amount` to the filled volume of `mint`. Once `MAX_MINTS` mints are tracked, volume in
+    /// other mints is not recorded, so the account keeps a fixed size.
+    pub fn record_volume(&mut self, mint: Pubkey, amount: u64) {
+        let full = self.volumes.len() >= TraderStats::MAX_MINTS;
+        match self.volumes.iter_mut().find(|volume| volume.mint == mint) {
+            Some(volume) => volume.amount = volume.amount.saturating_add(amount),
+            None if !full => self.volumes.push(MintVolume { mint, amount }),
+            None => {}
+        }
+    }
+}
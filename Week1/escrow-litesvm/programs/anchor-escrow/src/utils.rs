@@ -0,0 +1,31 @@
+use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
+
+/// Grows `account` to at least `space` bytes, topping up its rent from `payer`
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+) -> Result<()> {
+    if account.data_len() >= space {
+        return Ok(());
+    }
+
+    let rent_due = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(account.lamports());
+
+    if rent_due > 0 {
+        let cpi_accounts = Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.clone(), cpi_accounts);
+
+        transfer(cpi_ctx, rent_due)?;
+    }
+
+    account.realloc(space, false)?;
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+//! Client side of the whitelist transfer hook: builds `transfer_checked` instructions for hooked
+//! mints with every extra account the hook needs, resolved from the mint's `extra-account-metas`
+//! PDA. Resolution is generic, so the same helpers work for any transfer hook program, including
+//! challenge-program.
+//!
+//! [`snapshot`] exports and re-imports the whitelist entries of a deployment of either program.
+
+use std::future::Future;
+
+use anchor_lang::solana_program::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::StateWithExtensions,
+    offchain::create_transfer_checked_instruction_with_extra_metas,
+    state::Mint,
+};
+pub use anchor_spl::token_2022::spl_token_2022::offchain::{AccountDataResult, AccountFetchError};
+pub use whitelist_transfer_hook::{errors::WhitelistTransferHookError, ID as PROGRAM_ID};
+pub use challenge_program::{
+    errors::WhitelistTransferHookError as ChallengeProgramError, ID as CHALLENGE_PROGRAM_ID,
+};
+
+pub mod pda;
+pub mod snapshot;
+
+#[cfg(test)]
+mod tests;
+
+/// Builds a `transfer_checked` of `amount` base units from `source` to `destination`, signed by
+/// `authority` (the source owner, a delegate or a multisig with `multisig_signers`).
+///
+/// The mint's decimals and hook program are read from the mint, and the hook's extra accounts
+/// from its `extra-account-metas` PDA, through `fetch_account_data`. It returns the data of an
+/// account, `None` if it does not exist, e.g. `|address| rpc.get_account(&address)` mapped to
+/// the account data.
+#[allow(clippy::too_many_arguments)]
+pub async fn transfer_checked<F, Fut>(
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    multisig_signers: &[&Pubkey],
+    amount: u64,
+    fetch_account_data: F,
+) -> Result<Instruction, AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>,
+{
+    let mint_data = fetch_account_data(*mint)
+        .await?
+        .ok_or(ProgramError::UninitializedAccount)?;
+    let decimals = StateWithExtensions::<Mint>::unpack(&mint_data)?.base.decimals;
+
+    create_transfer_checked_instruction_with_extra_metas(
+        &spl_token_2022::ID,
+        source,
+        mint,
+        destination,
+        authority,
+        multisig_signers,
+        amount,
+        decimals,
+        fetch_account_data,
+    )
+    .await
+}
+
+/// Same as [`transfer_checked`] for clients whose account fetches are blocking
+pub fn transfer_checked_blocking<F>(
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    multisig_signers: &[&Pubkey],
+    amount: u64,
+    fetch_account_data: F,
+) -> Result<Instruction, AccountFetchError>
+where
+    F: Fn(&Pubkey) -> AccountDataResult,
+{
+    futures::executor::block_on(transfer_checked(
+        source,
+        mint,
+        destination,
+        authority,
+        multisig_signers,
+        amount,
+        |address| std::future::ready(fetch_account_data(&address)),
+    ))
+}
+
+/// Hook error that rejected a transfer, tagged with the hook program that raised it
+#[derive(Clone, Copy, Debug)]
+pub enum HookError {
+    Whitelist(WhitelistTransferHookError),
+    Challenge(ChallengeProgramError),
+}
+
+impl HookError {
+    pub fn name(&self) -> String {
+        match self {
+            HookError::Whitelist(error) => error.name(),
+            HookError::Challenge(error) => error.name(),
+        }
+    }
+}
+
+/// Maps the custom error code of a failed transfer back to the hook error that rejected it.
+/// `program_id` is the hook that failed, as both programs number their errors from the same offset.
+/// `None` for other programs and for codes raised by Anchor, the token program or the runtime.
+pub fn decode_error(program_id: &Pubkey, code: u32) -> Option<HookError> {
+    if *program_id == PROGRAM_ID {
+        WhitelistTransferHookError::from_code(code).map(HookError::Whitelist)
+    } else if program_id.to_bytes() == CHALLENGE_PROGRAM_ID.to_bytes() {
+        ChallengeProgramError::from_code(code).map(HookError::Challenge)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,41 @@
+//! Addresses of the accounts of the whitelist transfer hook program
+
+use anchor_lang::solana_program::pubkey::Pubkey;
+
+use crate::PROGRAM_ID;
+
+pub fn config() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &PROGRAM_ID).0
+}
+
+pub fn extra_account_metas(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &PROGRAM_ID).0
+}
+
+pub fn mint_config(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"mint_config", mint.as_ref()], &PROGRAM_ID).0
+}
+
+pub fn whitelist(mint: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"whitelist", mint.as_ref(), user.as_ref()], &PROGRAM_ID).0
+}
+
+pub fn exempt_authorities(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"exempt_authorities", mint.as_ref()], &PROGRAM_ID).0
+}
+
+pub fn attesters(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"attesters", mint.as_ref()], &PROGRAM_ID).0
+}
+
+pub fn credential(mint: &Pubkey, subject: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"credential", mint.as_ref(), subject.as_ref()], &PROGRAM_ID).0
+}
+
+pub fn multisig_approval(mint: &Pubkey, multisig: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"multisig", mint.as_ref(), multisig.as_ref()], &PROGRAM_ID).0
+}
+
+pub fn transfer_log(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"transfer_log", mint.as_ref()], &PROGRAM_ID).0
+}
@@ -0,0 +1,419 @@
+//! Export of every whitelist entry of a deployment, and re-import into another one. Used when
+//! the program moves to a new ID and the entries would otherwise be lost.
+//!
+//! Export decodes the accounts returned by `getProgramAccounts` filtered on
+//! [`WHITELIST_DISCRIMINATOR`] at offset 0. The whitelist hook and challenge-program share it,
+//! as both name their entries `Whitelist`, so the accounts must come from the program the
+//! snapshot is for: a [`Snapshot`] of [`WhitelistRecord`]s for the hook, of
+//! [`ChallengeWhitelistRecord`]s for challenge-program. Import yields the transactions that
+//! recreate the entries, and [`Snapshot::diff`] against a fresh export verifies it.
+
+use std::{collections::BTreeMap, fmt};
+
+use anchor_lang::{
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    AccountDeserialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use whitelist_transfer_hook::{accounts, instruction, state::Whitelist};
+
+/// Prefix of every whitelist entry's data, to filter program accounts on
+pub const WHITELIST_DISCRIMINATOR: &[u8] = Whitelist::DISCRIMINATOR;
+
+/// An entry of one program, as exported
+pub trait SnapshotRecord: Clone + fmt::Debug + Eq + Serialize + DeserializeOwned {
+    /// Identifies the entry across deployments
+    type Key: Copy + Ord;
+
+    /// Column names of the CSV form, in field order
+    const CSV_HEADER: &'static str;
+
+    fn key(&self) -> Self::Key;
+
+    /// Decodes an account's data, `None` if it is not a whitelist entry
+    fn decode(data: &[u8]) -> Option<Self>;
+}
+
+/// Entry of the whitelist transfer hook
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WhitelistRecord {
+    #[serde(with = "pubkey_string")]
+    pub mint: Pubkey,
+    #[serde(with = "pubkey_string")]
+    pub address: Pubkey,
+    pub is_whitelisted: bool,
+    pub valid_from: i64,
+    pub valid_until: Option<i64>,
+}
+
+impl WhitelistRecord {
+    // Entries created by `batch_add_to_whitelist` carry no validity window
+    fn has_window(&self) -> bool {
+        self.valid_from != 0 || self.valid_until.is_some()
+    }
+}
+
+impl SnapshotRecord for WhitelistRecord {
+    type Key = (Pubkey, Pubkey);
+
+    const CSV_HEADER: &'static str = "mint,address,is_whitelisted,valid_from,valid_until";
+
+    fn key(&self) -> Self::Key {
+        (self.mint, self.address)
+    }
+
+    fn decode(data: &[u8]) -> Option<Self> {
+        let whitelist = Whitelist::try_deserialize(&mut &data[..]).ok()?;
+        Some(Self {
+            mint: whitelist.mint,
+            address: whitelist.address,
+            is_whitelisted: whitelist.is_whitelisted,
+            valid_from: whitelist.valid_from,
+            valid_until: whitelist.valid_until,
+        })
+    }
+}
+
+/// Entry of challenge-program, with the user's balance in the vault
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChallengeWhitelistRecord {
+    #[serde(with = "pubkey_string")]
+    pub address: Pubkey,
+    pub is_whitelisted: bool,
+    pub deposited_amount: u64,
+}
+
+impl SnapshotRecord for ChallengeWhitelistRecord {
+    type Key = Pubkey;
+
+    const CSV_HEADER: &'static str = "address,is_whitelisted,deposited_amount";
+
+    fn key(&self) -> Self::Key {
+        self.address
+    }
+
+    fn decode(data: &[u8]) -> Option<Self> {
+        use challenge_anchor_lang::AccountDeserialize as _;
+
+        let whitelist = challenge_program::states::Whitelist::try_deserialize(&mut &data[..]).ok()?;
+        Some(Self {
+            address: Pubkey::new_from_array(whitelist.address.to_bytes()),
+            is_whitelisted: whitelist.is_whitelisted,
+            deposited_amount: whitelist.deposited_amount,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot<R = WhitelistRecord> {
+    /// Program the entries were exported from
+    #[serde(with = "pubkey_string")]
+    pub program_id: Pubkey,
+    /// Sorted by key: mint, then address for the hook
+    pub entries: Vec<R>,
+}
+
+/// Difference between an expected and an actual snapshot, for one entry
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntryDiff<R = WhitelistRecord> {
+    /// The entry is expected but was not found
+    Missing(R),
+    /// The entry was found but is not expected
+    Unexpected(R),
+    /// The entry exists on both sides with different fields
+    Changed {
+        expected: R,
+        actual: R,
+    },
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Json(serde_json::Error),
+    Csv(csv::Error),
+    CsvHeader { expected: &'static str, found: String },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Json(error) => write!(f, "invalid snapshot JSON: {error}"),
+            SnapshotError::Csv(error) => write!(f, "invalid snapshot CSV: {error}"),
+            SnapshotError::CsvHeader { expected, found } => {
+                write!(f, "invalid snapshot CSV: expected header `{expected}`, found `{found}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl<R: SnapshotRecord> Snapshot<R> {
+    /// Decodes the whitelist entries among `accounts` (address and data pairs) of `program_id`.
+    /// Accounts of other types are skipped.
+    pub fn export<I>(program_id: Pubkey, accounts: I) -> Self
+    where
+        I: IntoIterator<Item = (Pubkey, Vec<u8>)>,
+    {
+        let mut entries: Vec<R> = accounts
+            .into_iter()
+            .filter(|(_, data)| data.starts_with(WHITELIST_DISCRIMINATOR))
+            .filter_map(|(_, data)| R::decode(&data))
+            .collect();
+        entries.sort_by_key(R::key);
+
+        Self { program_id, entries }
+    }
+
+    pub fn to_json(&self) -> Result<String, SnapshotError> {
+        serde_json::to_string_pretty(self).map_err(SnapshotError::Json)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SnapshotError> {
+        serde_json::from_str(json).map_err(SnapshotError::Json)
+    }
+
+    /// One entry per line, an empty field for a `None`. The program ID is not part of the CSV.
+    pub fn to_csv(&self) -> Result<String, SnapshotError> {
+        let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+        writer.write_record(R::CSV_HEADER.split(',')).map_err(SnapshotError::Csv)?;
+        for entry in &self.entries {
+            writer.serialize(entry).map_err(SnapshotError::Csv)?;
+        }
+
+        let csv = writer.into_inner().map_err(|error| SnapshotError::Csv(error.into_error().into()))?;
+        Ok(String::from_utf8(csv).expect("CSV of pubkeys and numbers is UTF-8"))
+    }
+
+    /// Parses CSV as written by [`Snapshot::to_csv`]. Fields may be quoted and padded with spaces.
+    pub fn from_csv(program_id: Pubkey, csv: &str) -> Result<Self, SnapshotError> {
+        let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(csv.as_bytes());
+
+        let header = reader.headers().map_err(SnapshotError::Csv)?;
+        if !header.iter().eq(R::CSV_HEADER.split(',')) {
+            return Err(SnapshotError::CsvHeader {
+                expected: R::CSV_HEADER,
+                found: header.iter().collect::<Vec<_>>().join(","),
+            });
+        }
+
+        let mut entries = reader
+            .deserialize()
+            .collect::<Result<Vec<R>, _>>()
+            .map_err(SnapshotError::Csv)?;
+        entries.sort_by_key(R::key);
+
+        Ok(Self { program_id, entries })
+    }
+
+    /// Entries that differ between this (expected) snapshot and `actual`, matched by key. Empty
+    /// when the import is complete.
+    pub fn diff(&self, actual: &Snapshot<R>) -> Vec<EntryDiff<R>> {
+        let expected: BTreeMap<_, _> = self.entries.iter().map(|entry| (entry.key(), entry)).collect();
+        let actual: BTreeMap<_, _> = actual.entries.iter().map(|entry| (entry.key(), entry)).collect();
+
+        let mut diffs = Vec::new();
+        for (entry_key, expected_entry) in &expected {
+            match actual.get(entry_key) {
+                None => diffs.push(EntryDiff::Missing((*expected_entry).clone())),
+                Some(actual_entry) if actual_entry != expected_entry => diffs.push(EntryDiff::Changed {
+                    expected: (*expected_entry).clone(),
+                    actual: (*actual_entry).clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (entry_key, actual_entry) in &actual {
+            if !expected.contains_key(entry_key) {
+                diffs.push(EntryDiff::Unexpected((*actual_entry).clone()));
+            }
+        }
+
+        diffs
+    }
+}
+
+impl Snapshot<WhitelistRecord> {
+    /// Transactions that recreate the entries under `program_id`, signed by `operator`, which
+    /// needs the whitelist operator role on every mint. Each inner `Vec` must be sent as one
+    /// transaction, so that no transfer sees an entry half restored.
+    ///
+    /// Entries are created whitelisted with `batch_add_to_whitelist`, `batch_size` per
+    /// transaction. In the same transaction, entries that are not whitelisted are cleared with
+    /// `batch_remove_from_whitelist` and windows are restored with `set_whitelist_validity`.
+    /// Entries with a window are batched apart from the others: `set_whitelist_validity` only
+    /// accepts users that are system accounts, so a batch holding the entry of a program-owned
+    /// account fails as a whole, and its entries show up as missing in [`Snapshot::diff`].
+    pub fn import_instructions(
+        &self,
+        program_id: &Pubkey,
+        operator: &Pubkey,
+        batch_size: usize,
+    ) -> Vec<Vec<Instruction>> {
+        let mut by_mint: BTreeMap<Pubkey, Vec<&WhitelistRecord>> = BTreeMap::new();
+        for entry in &self.entries {
+            by_mint.entry(entry.mint).or_default().push(entry);
+        }
+
+        let mut transactions = Vec::new();
+        for (mint, entries) in by_mint {
+            let mint_config = Pubkey::find_program_address(&[b"mint_config", mint.as_ref()], program_id).0;
+            let whitelist = |user: &Pubkey| {
+                Pubkey::find_program_address(&[b"whitelist", mint.as_ref(), user.as_ref()], program_id).0
+            };
+            let batch = |users: &[Pubkey], data: Vec<u8>| {
+                let mut accounts = accounts::BatchWhitelistOperations {
+                    operator: *operator,
+                    mint,
+                    mint_config,
+                    system_program: system_program::ID,
+                }
+                .to_account_metas(None);
+                accounts.extend(users.iter().map(|user| AccountMeta::new(whitelist(user), false)));
+
+                Instruction {
+                    program_id: *program_id,
+                    accounts,
+                    data,
+                }
+            };
+            let set_validity = |entry: &WhitelistRecord| Instruction {
+                program_id: *program_id,
+                accounts: accounts::WhitelistOperations {
+                    operator: *operator,
+                    mint,
+                    mint_config,
+                    whitelist: whitelist(&entry.address),
+                    user: entry.address,
+                    system_program: system_program::ID,
+                }
+                .to_account_metas(None),
+                data: instruction::SetWhitelistValidity {
+                    user: entry.address,
+                    valid_from: entry.valid_from,
+                    valid_until: entry.valid_until,
+                }
+                .data(),
+            };
+
+            for (is_whitelisted, has_window) in [(true, false), (true, true), (false, false), (false, true)] {
+                let group: Vec<&WhitelistRecord> = entries
+                    .iter()
+                    .copied()
+                    .filter(|entry| entry.is_whitelisted == is_whitelisted && entry.has_window() == has_window)
+                    .collect();
+
+                for chunk in group.chunks(batch_size.max(1)) {
+                    let users: Vec<Pubkey> = chunk.iter().map(|entry| entry.address).collect();
+
+                    // There is no batch creation of entries that are not whitelisted, so they are
+                    // created whitelisted and removed right after
+                    let mut transaction =
+                        vec![batch(&users, instruction::BatchAddToWhitelist { users: users.clone() }.data())];
+                    if !is_whitelisted {
+                        transaction.push(batch(
+                            &users,
+                            instruction::BatchRemoveFromWhitelist { users: users.clone() }.data(),
+                        ));
+                    }
+                    if has_window {
+                        transaction.extend(chunk.iter().map(|entry| set_validity(entry)));
+                    }
+                    transactions.push(transaction);
+                }
+            }
+        }
+
+        transactions
+    }
+}
+
+impl Snapshot<ChallengeWhitelistRecord> {
+    /// Transactions that recreate the entries under `program_id`, one per entry, signed by
+    /// `operator`. Each entry is created with its balance by `initialize_whitelist` and, when
+    /// whitelisted, added in the same transaction. Only the super-admin may restore balances, so
+    /// `operator` must be the super-admin unless every balance is zero.
+    pub fn import_instructions(&self, program_id: &Pubkey, operator: &Pubkey) -> Vec<Vec<Instruction>> {
+        use challenge_anchor_lang::{InstructionData as _, ToAccountMetas as _};
+        use challenge_program::{accounts as challenge_accounts, instruction as challenge_instruction};
+
+        // challenge-program builds on an older Anchor, with its own pubkey and account meta types
+        let key = |pubkey: &Pubkey| challenge_anchor_lang::prelude::Pubkey::new_from_array(pubkey.to_bytes());
+        let metas = |metas: Vec<challenge_anchor_lang::prelude::AccountMeta>| -> Vec<AccountMeta> {
+            metas
+                .into_iter()
+                .map(|meta| AccountMeta {
+                    pubkey: Pubkey::new_from_array(meta.pubkey.to_bytes()),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect()
+        };
+
+        let config = Pubkey::find_program_address(&[b"config"], program_id).0;
+
+        self.entries
+            .iter()
+            .map(|entry| {
+                let user = entry.address;
+                let whitelist = Pubkey::find_program_address(&[b"whitelist", user.as_ref()], program_id).0;
+
+                let mut transaction = vec![Instruction {
+                    program_id: *program_id,
+                    accounts: metas(
+                        challenge_accounts::InitializeWhitelist {
+                            operator: key(operator),
+                            config: key(&config),
+                            whitelist: key(&whitelist),
+                            system_program: key(&system_program::ID),
+                        }
+                        .to_account_metas(None),
+                    ),
+                    data: challenge_instruction::InitializeWhitelist {
+                        user: key(&user),
+                        amount: entry.deposited_amount,
+                    }
+                    .data(),
+                }];
+                if entry.is_whitelisted {
+                    transaction.push(Instruction {
+                        program_id: *program_id,
+                        accounts: metas(
+                            challenge_accounts::WhitelistOperations {
+                                operator: key(operator),
+                                whitelist: key(&whitelist),
+                                config: key(&config),
+                                user: key(&user),
+                                system_program: key(&system_program::ID),
+                            }
+                            .to_account_metas(None),
+                        ),
+                        data: challenge_instruction::AddToWhitelist { user: key(&user) }.data(),
+                    });
+                }
+                transaction
+            })
+            .collect()
+    }
+}
+
+// Pubkeys as base58 strings, as explorers and the CLI show them
+mod pubkey_string {
+    use std::str::FromStr;
+
+    use anchor_lang::solana_program::pubkey::Pubkey;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        Pubkey::from_str(&string).map_err(D::Error::custom)
+    }
+}
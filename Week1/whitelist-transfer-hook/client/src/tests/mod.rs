@@ -0,0 +1,506 @@
+#[cfg(test)]
+mod transfer_checked_tests {
+    use {
+        std::collections::HashMap,
+        anchor_lang::solana_program::{
+            instruction::AccountMeta,
+            program_option::COption,
+            program_pack::Pack,
+            pubkey::Pubkey,
+        },
+        anchor_spl::{
+            token_2022::spl_token_2022::{
+                self,
+                extension::{transfer_hook::TransferHook, BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut},
+                state::{Account, AccountState, Mint},
+            },
+            token_2022_extensions::spl_pod::optional_keys::OptionalNonZeroPubkey,
+        },
+        spl_tlv_account_resolution::state::ExtraAccountMetaList,
+        spl_transfer_hook_interface::instruction::ExecuteInstruction,
+        whitelist_transfer_hook::instructions::InitializeExtraAccountMetaList,
+    };
+
+    use crate::{pda, transfer_checked_blocking, AccountDataResult, PROGRAM_ID};
+
+    const DECIMALS: u8 = 6;
+
+    struct Accounts {
+        mint: Pubkey,
+        source: Pubkey,
+        destination: Pubkey,
+        owner: Pubkey,
+        recipient: Pubkey,
+        data: HashMap<Pubkey, Vec<u8>>,
+    }
+
+    impl Accounts {
+        // A hooked mint with its extra account metas, and token accounts of `owner` and `recipient`
+        fn new() -> Self {
+            let mint = Pubkey::new_unique();
+            let owner = Pubkey::new_unique();
+            let recipient = Pubkey::new_unique();
+            let source = Pubkey::new_unique();
+            let destination = Pubkey::new_unique();
+
+            let space = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook]).unwrap();
+            let mut mint_data = vec![0; space];
+            let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut mint_data).unwrap();
+            state.init_extension::<TransferHook>(true).unwrap().program_id =
+                OptionalNonZeroPubkey::try_from(Some(PROGRAM_ID)).unwrap();
+            state.base = Mint {
+                mint_authority: COption::Some(owner),
+                supply: 0,
+                decimals: DECIMALS,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            };
+            state.pack_base();
+            state.init_account_type().unwrap();
+
+            let token_account = |owner: &Pubkey| {
+                let mut data = vec![0; Account::LEN];
+                Account {
+                    mint,
+                    owner: *owner,
+                    amount: 100,
+                    state: AccountState::Initialized,
+                    ..Account::default()
+                }
+                .pack_into_slice(&mut data);
+                data
+            };
+
+            let extra_account_metas = InitializeExtraAccountMetaList::extra_account_metas().unwrap();
+            let mut meta_list_data = vec![0; ExtraAccountMetaList::size_of(extra_account_metas.len()).unwrap()];
+            ExtraAccountMetaList::init::<ExecuteInstruction>(&mut meta_list_data, &extra_account_metas).unwrap();
+
+            let data = HashMap::from([
+                (mint, mint_data),
+                (source, token_account(&owner)),
+                (destination, token_account(&recipient)),
+                (pda::extra_account_metas(&mint), meta_list_data),
+            ]);
+
+            Self { mint, source, destination, owner, recipient, data }
+        }
+
+        fn fetch(&self, address: &Pubkey) -> AccountDataResult {
+            Ok(self.data.get(address).cloned())
+        }
+    }
+
+    // The accounts the hook resolves after the four transfer accounts, in extra account meta order
+    fn hook_accounts(mint: &Pubkey, authority: &Pubkey, owner: &Pubkey, recipient: &Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(pda::whitelist(mint, authority), false),
+            AccountMeta::new_readonly(pda::whitelist(mint, recipient), false),
+            AccountMeta::new_readonly(pda::mint_config(mint), false),
+            AccountMeta::new_readonly(pda::exempt_authorities(mint), false),
+            AccountMeta::new_readonly(pda::attesters(mint), false),
+            AccountMeta::new_readonly(pda::credential(mint, authority), false),
+            AccountMeta::new_readonly(pda::credential(mint, recipient), false),
+            AccountMeta::new_readonly(*recipient, false),
+            AccountMeta::new(pda::transfer_log(mint), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(pda::whitelist(mint, owner), false),
+            AccountMeta::new_readonly(pda::credential(mint, owner), false),
+            AccountMeta::new_readonly(pda::multisig_approval(mint, authority), false),
+            AccountMeta::new_readonly(pda::multisig_approval(mint, recipient), false),
+            AccountMeta::new_readonly(pda::multisig_approval(mint, owner), false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+            AccountMeta::new_readonly(pda::extra_account_metas(mint), false),
+        ]
+    }
+
+    #[test]
+    fn test_resolves_hook_accounts() {
+        let accounts = Accounts::new();
+
+        let instruction = transfer_checked_blocking(
+            &accounts.source,
+            &accounts.mint,
+            &accounts.destination,
+            &accounts.owner,
+            &[],
+            42,
+            |address| accounts.fetch(address),
+        )
+        .unwrap();
+
+        assert_eq!(instruction.program_id, spl_token_2022::ID);
+        assert_eq!(
+            instruction.data,
+            spl_token_2022::instruction::TokenInstruction::TransferChecked { amount: 42, decimals: DECIMALS }.pack()
+        );
+        assert_eq!(
+            instruction.accounts[..4],
+            [
+                AccountMeta::new(accounts.source, false),
+                AccountMeta::new_readonly(accounts.mint, false),
+                AccountMeta::new(accounts.destination, false),
+                AccountMeta::new_readonly(accounts.owner, true),
+            ]
+        );
+        assert_eq!(
+            instruction.accounts[4..],
+            hook_accounts(&accounts.mint, &accounts.owner, &accounts.owner, &accounts.recipient)
+        );
+    }
+
+    #[test]
+    fn test_resolves_delegate_accounts() {
+        let accounts = Accounts::new();
+        let delegate = Pubkey::new_unique();
+
+        let instruction = transfer_checked_blocking(
+            &accounts.source,
+            &accounts.mint,
+            &accounts.destination,
+            &delegate,
+            &[],
+            42,
+            |address| accounts.fetch(address),
+        )
+        .unwrap();
+
+        // The source owner's accounts are read from the source token account, not the signer
+        assert_eq!(instruction.accounts[3], AccountMeta::new_readonly(delegate, true));
+        assert_eq!(
+            instruction.accounts[4..],
+            hook_accounts(&accounts.mint, &delegate, &accounts.owner, &accounts.recipient)
+        );
+    }
+
+    #[test]
+    fn test_missing_extra_account_metas() {
+        let mut accounts = Accounts::new();
+        accounts.data.remove(&pda::extra_account_metas(&accounts.mint));
+
+        assert!(transfer_checked_blocking(
+            &accounts.source,
+            &accounts.mint,
+            &accounts.destination,
+            &accounts.owner,
+            &[],
+            42,
+            |address| accounts.fetch(address),
+        )
+        .is_err());
+    }
+}
+
+#[cfg(test)]
+mod snapshot_tests {
+    use {
+        anchor_lang::{
+            solana_program::{instruction::Instruction, pubkey::Pubkey},
+            AccountSerialize, Discriminator,
+        },
+        whitelist_transfer_hook::state::{MintConfig, Whitelist},
+    };
+
+    use crate::snapshot::{ChallengeWhitelistRecord, EntryDiff, Snapshot, WhitelistRecord};
+
+    fn record(mint: Pubkey, is_whitelisted: bool, valid_until: Option<i64>) -> WhitelistRecord {
+        WhitelistRecord {
+            mint,
+            address: Pubkey::new_unique(),
+            is_whitelisted,
+            valid_from: 0,
+            valid_until,
+        }
+    }
+
+    fn whitelist_account(entry: &WhitelistRecord) -> (Pubkey, Vec<u8>) {
+        let mut data = Vec::new();
+        Whitelist {
+            mint: entry.mint,
+            address: entry.address,
+            is_whitelisted: entry.is_whitelisted,
+            valid_from: entry.valid_from,
+            valid_until: entry.valid_until,
+            revision: 0,
+            bump: 255,
+        }
+        .try_serialize(&mut data)
+        .unwrap();
+        (Pubkey::new_unique(), data)
+    }
+
+    fn snapshot() -> Snapshot {
+        let mint = Pubkey::new_unique();
+        let mut entries = vec![
+            record(mint, true, None),
+            record(mint, true, Some(1_700_000_000)),
+            record(mint, false, None),
+            record(Pubkey::new_unique(), true, None),
+        ];
+        entries.sort_by_key(|entry| (entry.mint, entry.address));
+
+        Snapshot {
+            program_id: Pubkey::new_unique(),
+            entries,
+        }
+    }
+
+    #[test]
+    fn test_export_keeps_only_whitelist_entries() {
+        let expected = snapshot();
+
+        let mut accounts: Vec<(Pubkey, Vec<u8>)> = expected.entries.iter().rev().map(whitelist_account).collect();
+        // A mint config and a truncated entry are skipped
+        accounts.push((Pubkey::new_unique(), MintConfig::DISCRIMINATOR.to_vec()));
+        accounts.push((Pubkey::new_unique(), Whitelist::DISCRIMINATOR.to_vec()));
+
+        assert_eq!(Snapshot::export(expected.program_id, accounts), expected);
+    }
+
+    #[test]
+    fn test_json_and_csv_round_trip() {
+        let snapshot = snapshot();
+
+        assert_eq!(Snapshot::from_json(&snapshot.to_json().unwrap()).unwrap(), snapshot);
+        let csv = snapshot.to_csv().unwrap();
+        assert_eq!(Snapshot::from_csv(snapshot.program_id, &csv).unwrap(), snapshot);
+
+        assert!(Snapshot::<WhitelistRecord>::from_csv(snapshot.program_id, "address\n").is_err());
+        let truncated = csv.replace(",true,", ",");
+        assert!(Snapshot::<WhitelistRecord>::from_csv(snapshot.program_id, &truncated).is_err());
+    }
+
+    #[test]
+    fn test_csv_quoted_fields() {
+        let snapshot = snapshot();
+        let entry = &snapshot.entries[0];
+        let header = "mint,address,is_whitelisted,valid_from,valid_until";
+
+        // Quotes and padding, as spreadsheets write them, are stripped
+        let quoted = format!(
+            "\"mint\",\"address\",\"is_whitelisted\",\"valid_from\",\"valid_until\"\n\"{}\",\"{}\", {} ,\"{}\",\"\"\n",
+            entry.mint, entry.address, entry.is_whitelisted, entry.valid_from
+        );
+        let parsed: Snapshot = Snapshot::from_csv(snapshot.program_id, &quoted).unwrap();
+        assert_eq!(parsed.entries, vec![entry.clone()]);
+
+        // A quoted comma stays inside its field instead of shifting the others
+        let embedded_comma = format!(
+            "{header}\n\"{},{}\",{},true,0,\n",
+            entry.mint, entry.address, entry.address
+        );
+        assert!(Snapshot::<WhitelistRecord>::from_csv(snapshot.program_id, &embedded_comma).is_err());
+
+        // Unbalanced field counts are rejected
+        let extra_field = format!("{header}\n{},{},true,0,,1\n", entry.mint, entry.address);
+        assert!(Snapshot::<WhitelistRecord>::from_csv(snapshot.program_id, &extra_field).is_err());
+    }
+
+    #[test]
+    fn test_import_instructions() {
+        let snapshot = snapshot();
+        let program_id = Pubkey::new_unique();
+        let operator = Pubkey::new_unique();
+
+        let transactions = snapshot.import_instructions(&program_id, &operator, 1);
+
+        // One transaction per entry, each creating it and finishing it in place
+        fn discriminators(transaction: &[Instruction]) -> Vec<&[u8]> {
+            transaction.iter().map(|instruction| &instruction.data[..8]).collect()
+        }
+        let batch_add = whitelist_transfer_hook::instruction::BatchAddToWhitelist::DISCRIMINATOR;
+        let batch_remove = whitelist_transfer_hook::instruction::BatchRemoveFromWhitelist::DISCRIMINATOR;
+        let set_validity = whitelist_transfer_hook::instruction::SetWhitelistValidity::DISCRIMINATOR;
+        assert_eq!(transactions.len(), 4);
+        assert_eq!(transactions.iter().filter(|transaction| discriminators(transaction) == [batch_add]).count(), 2);
+
+        // The entry that is not whitelisted is removed in the transaction that created it
+        let removal = transactions
+            .iter()
+            .find(|transaction| discriminators(transaction) == [batch_add, batch_remove])
+            .unwrap();
+        assert_eq!(removal[0].accounts, removal[1].accounts);
+
+        // The window is restored in the transaction that created the entry, never after it
+        let windowed = snapshot.entries.iter().find(|entry| entry.valid_until.is_some()).unwrap();
+        let window = transactions
+            .iter()
+            .find(|transaction| discriminators(transaction) == [batch_add, set_validity])
+            .unwrap();
+        let expected_whitelist = Pubkey::find_program_address(
+            &[b"whitelist", windowed.mint.as_ref(), windowed.address.as_ref()],
+            &program_id,
+        )
+        .0;
+        assert!(window[0].accounts.iter().any(|meta| meta.pubkey == expected_whitelist && meta.is_writable));
+        assert!(window[1].accounts.iter().any(|meta| meta.pubkey == windowed.address));
+
+        let instructions: Vec<&Instruction> = transactions.iter().flatten().collect();
+        assert!(instructions.iter().all(|instruction| instruction.program_id == program_id));
+        assert!(instructions
+            .iter()
+            .all(|instruction| instruction.accounts[0].pubkey == operator && instruction.accounts[0].is_signer));
+
+        // Many entries that are not whitelisted share their batches
+        let mint = Pubkey::new_unique();
+        let mut entries: Vec<WhitelistRecord> = (0..5).map(|_| record(mint, false, None)).collect();
+        entries.sort_by_key(|entry| (entry.mint, entry.address));
+        let not_whitelisted = Snapshot { program_id, entries };
+
+        let transactions = not_whitelisted.import_instructions(&program_id, &operator, 4);
+        assert_eq!(transactions.len(), 2);
+        for transaction in &transactions {
+            assert_eq!(discriminators(transaction), [batch_add, batch_remove]);
+        }
+        assert_eq!(transactions[0][0].accounts.len(), 4 + 4);
+        assert_eq!(transactions[1][0].accounts.len(), 4 + 1);
+
+        // Entries with a window are batched apart, removed and then given their window
+        let mut entries = vec![record(mint, false, Some(1_700_000_000)), record(mint, false, None)];
+        entries.sort_by_key(|entry| (entry.mint, entry.address));
+        let mixed = Snapshot { program_id, entries };
+
+        let transactions = mixed.import_instructions(&program_id, &operator, 4);
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(discriminators(&transactions[0]), [batch_add, batch_remove]);
+        assert_eq!(discriminators(&transactions[1]), [batch_add, batch_remove, set_validity]);
+    }
+
+    #[test]
+    fn test_diff() {
+        let expected = snapshot();
+        assert!(expected.diff(&expected).is_empty());
+
+        let mut actual = expected.clone();
+        let missing = actual.entries.remove(0);
+        actual.entries[0].is_whitelisted = !actual.entries[0].is_whitelisted;
+        let unexpected = record(missing.mint, true, None);
+        actual.entries.push(unexpected.clone());
+
+        let diffs = expected.diff(&actual);
+        assert_eq!(diffs.len(), 3);
+        assert!(diffs.contains(&EntryDiff::Missing(missing)));
+        assert!(diffs.contains(&EntryDiff::Unexpected(unexpected)));
+        assert!(diffs.contains(&EntryDiff::Changed {
+            expected: expected.entries[1].clone(),
+            actual: actual.entries[0].clone(),
+        }));
+    }
+
+    fn challenge_snapshot() -> Snapshot<ChallengeWhitelistRecord> {
+        let mut entries = vec![
+            ChallengeWhitelistRecord {
+                address: Pubkey::new_unique(),
+                is_whitelisted: true,
+                deposited_amount: 1_500,
+            },
+            ChallengeWhitelistRecord {
+                address: Pubkey::new_unique(),
+                is_whitelisted: false,
+                deposited_amount: 0,
+            },
+        ];
+        entries.sort_by_key(|entry| entry.address);
+
+        Snapshot {
+            program_id: Pubkey::new_unique(),
+            entries,
+        }
+    }
+
+    fn challenge_whitelist_account(entry: &ChallengeWhitelistRecord) -> (Pubkey, Vec<u8>) {
+        let mut data = Vec::new();
+        challenge_anchor_lang::AccountSerialize::try_serialize(
+            &challenge_program::states::Whitelist {
+                address: challenge_anchor_lang::prelude::Pubkey::new_from_array(entry.address.to_bytes()),
+                deposited_amount: entry.deposited_amount,
+                is_whitelisted: entry.is_whitelisted,
+                bump: 255,
+            },
+            &mut data,
+        )
+        .unwrap();
+        (Pubkey::new_unique(), data)
+    }
+
+    #[test]
+    fn test_challenge_program_snapshot() {
+        let expected = challenge_snapshot();
+
+        // Balances survive the export and both file formats
+        let accounts: Vec<(Pubkey, Vec<u8>)> =
+            expected.entries.iter().rev().map(challenge_whitelist_account).collect();
+        let exported = Snapshot::<ChallengeWhitelistRecord>::export(expected.program_id, accounts);
+        assert_eq!(exported, expected);
+        assert_eq!(Snapshot::from_json(&exported.to_json().unwrap()).unwrap(), expected);
+        let csv = exported.to_csv().unwrap();
+        assert!(csv.starts_with("address,is_whitelisted,deposited_amount\n"));
+        assert_eq!(Snapshot::from_csv(expected.program_id, &csv).unwrap(), expected);
+        assert!(Snapshot::<WhitelistRecord>::from_csv(expected.program_id, &csv).is_err());
+
+        let mut actual = expected.clone();
+        actual.entries[0].deposited_amount += 1;
+        assert_eq!(expected.diff(&actual).len(), 1);
+    }
+
+    #[test]
+    fn test_challenge_program_import_instructions() {
+        let snapshot = challenge_snapshot();
+        let program_id = Pubkey::new_unique();
+        let operator = Pubkey::new_unique();
+
+        let transactions = snapshot.import_instructions(&program_id, &operator);
+        assert_eq!(transactions.len(), 2);
+
+        for (entry, transaction) in snapshot.entries.iter().zip(&transactions) {
+            // The entry is created with its balance, then added when it was whitelisted
+            assert_eq!(transaction.len(), if entry.is_whitelisted { 2 } else { 1 });
+            let initialize = &transaction[0];
+            assert_eq!(&initialize.data[8..40], entry.address.as_ref());
+            assert_eq!(initialize.data[40..48], entry.deposited_amount.to_le_bytes());
+
+            let whitelist = Pubkey::find_program_address(&[b"whitelist", entry.address.as_ref()], &program_id).0;
+            for instruction in transaction {
+                assert_eq!(instruction.program_id, program_id);
+                assert!(instruction.accounts[0].pubkey == operator && instruction.accounts[0].is_signer);
+                assert!(instruction.accounts.iter().any(|meta| meta.pubkey == whitelist && meta.is_writable));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod decode_error_tests {
+    use anchor_lang::{error::ERROR_CODE_OFFSET, solana_program::pubkey::Pubkey};
+
+    use crate::{
+        decode_error, ChallengeProgramError, WhitelistTransferHookError, CHALLENGE_PROGRAM_ID,
+        PROGRAM_ID,
+    };
+
+    #[test]
+    fn test_decodes_by_program() {
+        let challenge_program_id = Pubkey::new_from_array(CHALLENGE_PROGRAM_ID.to_bytes());
+
+        // The same code means different errors depending on the hook that raised it
+        let code = u32::from(WhitelistTransferHookError::NotWhitelisted);
+        assert_eq!(code, ERROR_CODE_OFFSET);
+        assert_eq!(decode_error(&PROGRAM_ID, code).unwrap().name(), "NotWhitelisted");
+        assert_eq!(decode_error(&challenge_program_id, code).unwrap().name(), "AlreadyWhitelisted");
+
+        let code = u32::from(ChallengeProgramError::ExceedsLimit);
+        assert_eq!(decode_error(&challenge_program_id, code).unwrap().name(), "ExceedsLimit");
+
+        // Every error of either program decodes back to itself
+        for &error in WhitelistTransferHookError::ALL {
+            assert_eq!(decode_error(&PROGRAM_ID, error.into()).unwrap().name(), error.name());
+        }
+        for &error in ChallengeProgramError::ALL {
+            assert_eq!(decode_error(&challenge_program_id, error.into()).unwrap().name(), error.name());
+        }
+
+        // Unknown programs and codes outside the tables are left alone
+        assert!(decode_error(&Pubkey::new_unique(), code).is_none());
+        assert!(decode_error(&PROGRAM_ID, ERROR_CODE_OFFSET - 1).is_none());
+        assert!(decode_error(&challenge_program_id, ERROR_CODE_OFFSET + ChallengeProgramError::ALL.len() as u32).is_none());
+    }
+}
@@ -1,11 +1,96 @@
 use anchor_lang::prelude::*;
 
-#[error_code]
-pub enum WhitelistTransferHookError {
-    #[msg("User is not whitelisted")]
-    NotWhitelisted,
-    #[msg("User is already whitelisted")]
-    AlreadyWhitelisted,
-    #[msg("TransferHook: Not transferring")]
-    NotTransferring,
-}
\ No newline at end of file
+/// Declares the error enum and its `ALL` table from the same list of variants,
+/// so a new variant is picked up by `from_code` without a second list to maintain
+macro_rules! error_codes {
+    (
+        #[error_code]
+        pub enum $name:ident {
+            $( #[msg($msg:literal)] $variant:ident, )*
+        }
+    ) => {
+        #[error_code]
+        pub enum $name {
+            $( #[msg($msg)] $variant, )*
+        }
+
+        impl $name {
+            /// Every variant, in declaration order
+            pub const ALL: &'static [Self] = &[$( Self::$variant ),*];
+        }
+    };
+}
+
+error_codes! {
+    #[error_code]
+    pub enum WhitelistTransferHookError {
+        #[msg("User is not whitelisted")]
+        NotWhitelisted,
+        #[msg("User is already whitelisted")]
+        AlreadyWhitelisted,
+        #[msg("TransferHook: Not transferring")]
+        NotTransferring,
+        #[msg("Signer is not the config admin")]
+        Unauthorized,
+        #[msg("Signer is not the pending admin")]
+        NotPendingAdmin,
+        #[msg("Destination is not whitelisted")]
+        DestinationNotWhitelisted,
+        #[msg("User is blocklisted")]
+        Blocklisted,
+        #[msg("Whitelist entry is outside its validity window")]
+        OutsideValidityWindow,
+        #[msg("Validity window ends before it starts")]
+        InvalidValidityWindow,
+        #[msg("Transfers are paused for this mint")]
+        TransferPaused,
+        #[msg("Number of users and whitelist accounts differ")]
+        BatchLengthMismatch,
+        #[msg("Account is not the user's whitelist PDA for this mint")]
+        InvalidWhitelistAccount,
+        #[msg("Extra account metas could not be built or written")]
+        InvalidExtraAccountMetas,
+        #[msg("Only program derived addresses can be exempted")]
+        InvalidExemptAuthority,
+        #[msg("Authority is already exempt")]
+        ExemptAuthorityAlreadyRegistered,
+        #[msg("Authority is not exempt")]
+        ExemptAuthorityNotRegistered,
+        #[msg("Exempt authority registry is full")]
+        ExemptAuthoritiesFull,
+        #[msg("Signer is not a trusted attester for this mint")]
+        UntrustedAttester,
+        #[msg("Attester is already registered")]
+        AttesterAlreadyRegistered,
+        #[msg("Attester is not registered")]
+        AttesterNotRegistered,
+        #[msg("Attester registry is full")]
+        AttestersFull,
+        #[msg("Subject holds a live credential from another attester")]
+        CredentialIssuedByOther,
+        #[msg("Credential must expire in the future")]
+        InvalidCredentialExpiry,
+        #[msg("Role is already granted to this member")]
+        RoleAlreadyGranted,
+        #[msg("Role is not granted to this member")]
+        RoleNotGranted,
+        #[msg("No room left for role grants")]
+        RoleGrantsFull,
+        #[msg("Account is not a token-2022 multisig")]
+        NotAMultisig,
+        #[msg("A multisig signer is not whitelisted")]
+        SignerNotWhitelisted,
+        #[msg("Key is not a signer of the approved multisig")]
+        NotAMultisigSigner,
+        #[msg("Signer entry is unchanged since the approval")]
+        SignerEntryUnchanged,
+    }
+}
+
+impl WhitelistTransferHookError {
+    /// Maps the custom error code of a failed instruction of this program back to its variant,
+    /// `None` for codes raised by Anchor, the token program or the runtime
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.iter().copied().find(|error| u32::from(*error) == code)
+    }
+}
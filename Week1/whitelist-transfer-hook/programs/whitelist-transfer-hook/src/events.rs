@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::state::TransferDecision;
+
+/// Outcome of one entry of a batch whitelist operation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BatchOutcome {
+    /// The entry was created and whitelisted
+    Created,
+    Added,
+    Removed,
+    AlreadyWhitelisted,
+    NotWhitelisted,
+    /// There is no entry to remove the user from
+    NotFound,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct BatchEntryResult {
+    pub user: Pubkey,
+    pub outcome: BatchOutcome,
+}
+
+#[event]
+pub struct WhitelistBatchUpdated {
+    pub mint: Pubkey,
+    pub results: Vec<BatchEntryResult>,
+}
+
+/// Emitted by the hook for every transfer it lets through
+#[event]
+pub struct TransferChecked {
+    pub mint: Pubkey,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub source_owner: Pubkey,
+    pub destination_owner: Pubkey,
+    pub amount: u64,
+    pub decision: TransferDecision,
+    pub source_whitelisted: bool,
+    pub destination_whitelisted: bool,
+    pub slot: u64,
+}
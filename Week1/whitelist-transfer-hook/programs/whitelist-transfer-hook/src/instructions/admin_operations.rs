@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::{Config, MintConfig};
+use crate::errors::WhitelistTransferHookError;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> ProposeAdmin<'info> {
+    pub fn propose_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        // The handover only completes once the new admin signs accept_admin
+        self.config.pending_admin = Some(new_admin);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.pending_admin == Some(new_admin.key()) @ WhitelistTransferHookError::NotPendingAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> AcceptAdmin<'info> {
+    pub fn accept_admin(&mut self) -> Result<()> {
+        self.config.admin = self.new_admin.key();
+        self.config.pending_admin = None;
+        Ok(())
+    }
+}
+
+/// Lets the deployment admin hand a mint's whitelist over to a new admin
+#[derive(Accounts)]
+pub struct SetMintAdmin<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+impl<'info> SetMintAdmin<'info> {
+    pub fn set_mint_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        msg!("Admin of mint {} set from {} to {}", self.mint.key(), self.mint_config.admin, new_admin);
+        self.mint_config.admin = new_admin;
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::{Attesters, Credential, MintConfig};
+use crate::errors::WhitelistTransferHookError;
+
+#[derive(Accounts)]
+pub struct AttesterOperations<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        has_one = admin @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = Attesters::INIT_SPACE + Attesters::DISCRIMINATOR.len(),
+        seeds = [b"attesters", mint.key().as_ref()],
+        bump
+    )]
+    pub attesters: Account<'info, Attesters>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AttesterOperations<'info> {
+    pub fn add_attester(&mut self, bumps: &AttesterOperationsBumps, attester: Pubkey) -> Result<()> {
+        require!(
+            !self.attesters.attesters.contains(&attester),
+            WhitelistTransferHookError::AttesterAlreadyRegistered
+        );
+        require!(
+            self.attesters.attesters.len() < Attesters::MAX_ATTESTERS,
+            WhitelistTransferHookError::AttestersFull
+        );
+
+        self.attesters.mint = self.mint.key();
+        self.attesters.bump = bumps.attesters;
+        self.attesters.attesters.push(attester);
+
+        msg!("Attester registered: {}", attester);
+        Ok(())
+    }
+
+    pub fn remove_attester(&mut self, attester: Pubkey) -> Result<()> {
+        let index = self
+            .attesters
+            .attesters
+            .iter()
+            .position(|registered| registered == &attester)
+            .ok_or(WhitelistTransferHookError::AttesterNotRegistered)?;
+
+        // Credentials it issued stop counting right away
+        self.attesters.attesters.swap_remove(index);
+
+        msg!("Attester removed: {}", attester);
+        Ok(())
+    }
+
+    pub fn set_required_claim(&mut self, bumps: &AttesterOperationsBumps, claim: u16) -> Result<()> {
+        self.attesters.mint = self.mint.key();
+        self.attesters.bump = bumps.attesters;
+        self.attesters.required_claim = claim;
+
+        msg!("Required claim set to {}", claim);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(subject: Pubkey)]
+pub struct IssueCredential<'info> {
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"attesters", mint.key().as_ref()],
+        bump = attesters.bump,
+        constraint = attesters.attesters.contains(&issuer.key()) @ WhitelistTransferHookError::UntrustedAttester,
+    )]
+    pub attesters: Account<'info, Attesters>,
+    #[account(
+        init_if_needed,
+        payer = issuer,
+        space = Credential::INIT_SPACE + Credential::DISCRIMINATOR.len(),
+        seeds = [b"credential", mint.key().as_ref(), subject.key().as_ref()],
+        bump
+    )]
+    pub credential: Account<'info, Credential>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> IssueCredential<'info> {
+    pub fn issue_credential(
+        &mut self,
+        bumps: &IssueCredentialBumps,
+        subject: Pubkey,
+        claim: u16,
+        expires_at: i64,
+    ) -> Result<()> {
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            WhitelistTransferHookError::InvalidCredentialExpiry
+        );
+
+        // A subject holds one credential per mint, which only its issuer may renew while it is live
+        let existing = &self.credential;
+        let replaceable = existing.issuer == Pubkey::default()
+            || existing.issuer == self.issuer.key()
+            || existing.revoked
+            || !self.attesters.attesters.contains(&existing.issuer);
+        require!(replaceable, WhitelistTransferHookError::CredentialIssuedByOther);
+
+        self.credential.set_inner(Credential {
+            mint: self.mint.key(),
+            subject,
+            issuer: self.issuer.key(),
+            claim,
+            expires_at,
+            revoked: false,
+            bump: bumps.credential,
+        });
+
+        msg!("Credential issued to {} by {}", subject, self.issuer.key());
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(subject: Pubkey)]
+pub struct RevokeCredential<'info> {
+    pub issuer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"credential", mint.key().as_ref(), subject.key().as_ref()],
+        bump = credential.bump,
+        has_one = issuer @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub credential: Account<'info, Credential>,
+}
+
+impl<'info> RevokeCredential<'info> {
+    pub fn revoke_credential(&mut self, subject: Pubkey) -> Result<()> {
+        self.credential.revoked = true;
+        msg!("Credential of {} revoked", subject);
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer},
+};
+use anchor_spl::token_interface::Mint;
+
+use crate::events::{BatchEntryResult, BatchOutcome, WhitelistBatchUpdated};
+use crate::state::{MintConfig, Role, Whitelist};
+use crate::errors::WhitelistTransferHookError;
+
+/// Whitelist operations over many users at once. The whitelist PDAs of `users` are passed,
+/// writable and in the same order, as remaining accounts.
+#[derive(Accounts)]
+pub struct BatchWhitelistOperations<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        constraint = mint_config.has_role(Role::WhitelistOperator, &operator.key()) @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> BatchWhitelistOperations<'info> {
+    pub fn batch_add_to_whitelist(
+        &mut self,
+        users: Vec<Pubkey>,
+        whitelists: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require_eq!(users.len(), whitelists.len(), WhitelistTransferHookError::BatchLengthMismatch);
+
+        let mut results = Vec::with_capacity(users.len());
+
+        for (user, info) in users.into_iter().zip(whitelists) {
+            let bump = self.check_whitelist_address(&user, info)?;
+
+            let outcome = if info.owner != &crate::ID {
+                self.create_whitelist(user, info, bump)?;
+                BatchOutcome::Created
+            } else {
+                let mut whitelist = Account::<Whitelist>::try_from(info)?;
+                if whitelist.is_whitelisted {
+                    BatchOutcome::AlreadyWhitelisted
+                } else {
+                    whitelist.is_whitelisted = true;
+                    whitelist.exit(&crate::ID)?;
+                    BatchOutcome::Added
+                }
+            };
+
+            results.push(BatchEntryResult { user, outcome });
+        }
+
+        emit!(WhitelistBatchUpdated {
+            mint: self.mint.key(),
+            results,
+        });
+
+        Ok(())
+    }
+
+    pub fn batch_remove_from_whitelist(
+        &mut self,
+        users: Vec<Pubkey>,
+        whitelists: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require_eq!(users.len(), whitelists.len(), WhitelistTransferHookError::BatchLengthMismatch);
+
+        let mut results = Vec::with_capacity(users.len());
+
+        for (user, info) in users.into_iter().zip(whitelists) {
+            self.check_whitelist_address(&user, info)?;
+
+            let outcome = if info.owner != &crate::ID {
+                BatchOutcome::NotFound
+            } else {
+                let mut whitelist = Account::<Whitelist>::try_from(info)?;
+                if whitelist.is_whitelisted {
+                    whitelist.is_whitelisted = false;
+                    whitelist.note_change();
+                    whitelist.exit(&crate::ID)?;
+                    BatchOutcome::Removed
+                } else {
+                    BatchOutcome::NotWhitelisted
+                }
+            };
+
+            results.push(BatchEntryResult { user, outcome });
+        }
+
+        emit!(WhitelistBatchUpdated {
+            mint: self.mint.key(),
+            results,
+        });
+
+        Ok(())
+    }
+
+    // Makes sure the remaining account is the user's whitelist PDA for this mint, returns its bump
+    fn check_whitelist_address(&self, user: &Pubkey, info: &AccountInfo) -> Result<u8> {
+        let mint = self.mint.key();
+        let (address, bump) =
+            Pubkey::find_program_address(&[b"whitelist", mint.as_ref(), user.as_ref()], &crate::ID);
+
+        require_keys_eq!(info.key(), address, WhitelistTransferHookError::InvalidWhitelistAccount);
+        require!(info.is_writable, WhitelistTransferHookError::InvalidWhitelistAccount);
+
+        Ok(bump)
+    }
+
+    fn create_whitelist(&self, user: Pubkey, info: &AccountInfo<'info>, bump: u8) -> Result<()> {
+        let mint = self.mint.key();
+        let seeds: &[&[u8]] = &[b"whitelist", mint.as_ref(), user.as_ref(), &[bump]];
+        let space = Whitelist::INIT_SPACE + Whitelist::DISCRIMINATOR.len();
+
+        let rent = Rent::get()?.minimum_balance(space);
+        let system_program = self.system_program.to_account_info();
+
+        // Anyone can send lamports to the PDA ahead of time, create_account would then fail.
+        // Like Anchor's `init`, top up the rent and allocate and assign the funded account instead.
+        let lamports = info.lamports();
+        if lamports == 0 {
+            create_account(
+                CpiContext::new_with_signer(
+                    system_program,
+                    CreateAccount {
+                        from: self.operator.to_account_info(),
+                        to: info.clone(),
+                    },
+                    &[seeds],
+                ),
+                rent,
+                space as u64,
+                &crate::ID,
+            )?;
+        } else {
+            let shortfall = rent.saturating_sub(lamports);
+            if shortfall > 0 {
+                transfer(
+                    CpiContext::new(
+                        system_program.clone(),
+                        Transfer {
+                            from: self.operator.to_account_info(),
+                            to: info.clone(),
+                        },
+                    ),
+                    shortfall,
+                )?;
+            }
+            allocate(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    Allocate { account_to_allocate: info.clone() },
+                    &[seeds],
+                ),
+                space as u64,
+            )?;
+            assign(
+                CpiContext::new_with_signer(
+                    system_program,
+                    Assign { account_to_assign: info.clone() },
+                    &[seeds],
+                ),
+                &crate::ID,
+            )?;
+        }
+
+        let whitelist = Whitelist {
+            mint,
+            address: user,
+            is_whitelisted: true,
+            valid_from: 0,
+            valid_until: None,
+            revision: 0,
+            bump,
+        };
+        whitelist.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::{MintConfig, Role, Whitelist};
+use crate::errors::WhitelistTransferHookError;
+
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct CloseWhitelistEntry<'info> {
+    pub operator: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        constraint = mint_config.has_role(Role::WhitelistOperator, &operator.key()) @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    // Once closed, the hook treats the user as having no entry
+    #[account(
+        mut,
+        close = rent_receiver,
+        seeds = [b"whitelist", mint.key().as_ref(), user.key().as_ref()],
+        bump = whitelist.bump,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+    #[account(mut)]
+    pub rent_receiver: SystemAccount<'info>,
+}
+
+impl<'info> CloseWhitelistEntry<'info> {
+    pub fn close_whitelist_entry(&mut self, user: Pubkey) -> Result<()> {
+        msg!("Closing whitelist entry of {}, rent to {}", user, self.rent_receiver.key());
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::{ExemptAuthorities, MintConfig};
+use crate::errors::WhitelistTransferHookError;
+
+#[derive(Accounts)]
+pub struct ExemptAuthorityOperations<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        has_one = admin @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = ExemptAuthorities::INIT_SPACE + ExemptAuthorities::DISCRIMINATOR.len(),
+        seeds = [b"exempt_authorities", mint.key().as_ref()],
+        bump
+    )]
+    pub exempt_authorities: Account<'info, ExemptAuthorities>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExemptAuthorityOperations<'info> {
+    pub fn add_exempt_authority(
+        &mut self,
+        bumps: &ExemptAuthorityOperationsBumps,
+        authority: Pubkey,
+    ) -> Result<()> {
+        // Only PDAs, whose program decides what they sign. A keypair signs whatever its holder wants.
+        require!(
+            !authority.is_on_curve(),
+            WhitelistTransferHookError::InvalidExemptAuthority
+        );
+        require!(
+            !self.exempt_authorities.authorities.contains(&authority),
+            WhitelistTransferHookError::ExemptAuthorityAlreadyRegistered
+        );
+        require!(
+            self.exempt_authorities.authorities.len() < ExemptAuthorities::MAX_AUTHORITIES,
+            WhitelistTransferHookError::ExemptAuthoritiesFull
+        );
+
+        self.exempt_authorities.mint = self.mint.key();
+        self.exempt_authorities.bump = bumps.exempt_authorities;
+        self.exempt_authorities.authorities.push(authority);
+
+        msg!("Exempt authority registered: {}", authority);
+        Ok(())
+    }
+
+    pub fn remove_exempt_authority(&mut self, authority: Pubkey) -> Result<()> {
+        let index = self
+            .exempt_authorities
+            .authorities
+            .iter()
+            .position(|exempt| exempt == &authority)
+            .ok_or(WhitelistTransferHookError::ExemptAuthorityNotRegistered)?;
+
+        self.exempt_authorities.authorities.swap_remove(index);
+
+        msg!("Exempt authority removed: {}", authority);
+        Ok(())
+    }
+}
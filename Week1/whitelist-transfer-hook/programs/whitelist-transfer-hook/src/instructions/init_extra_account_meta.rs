@@ -2,16 +2,26 @@ use anchor_lang::prelude::*;
 use anchor_spl::token_interface::Mint;
 use spl_tlv_account_resolution::{
     account::ExtraAccountMeta, 
+    pubkey_data::PubkeyData,
     state::ExtraAccountMetaList,
     seeds::Seed,
 };
 
-use crate::ID;
+use crate::state::{Config, DelegatePolicy, MintConfig, TransferPolicy};
+use crate::errors::WhitelistTransferHookError;
 
 #[derive(Accounts)]
 pub struct InitializeExtraAccountMetaList<'info> {
     #[account(mut)]
     payer: Signer<'info>,
+    // The deployment admin approves every mint the hook serves
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
 
     /// CHECK: ExtraAccountMetaList Account, must use these seeds
     #[account(
@@ -20,27 +30,235 @@ pub struct InitializeExtraAccountMetaList<'info> {
         bump,
         space = ExtraAccountMetaList::size_of(
             InitializeExtraAccountMetaList::extra_account_metas()?.len()
-        ).unwrap(),
+        ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
         payer = payer
     )]
     pub extra_account_meta_list: AccountInfo<'info>,
+    // Only the mint authority may attach the hook, and becomes the mint's whitelist admin
+    #[account(
+        mint::authority = payer,
+    )]
     pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        space = MintConfig::INIT_SPACE + MintConfig::DISCRIMINATOR.len(),
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_config: Account<'info, MintConfig>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> InitializeExtraAccountMetaList<'info> {
     pub fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
-        // Derive the whitelist PDA using our program ID
-        let account_meta = vec![ExtraAccountMeta::new_with_seeds(
-            &[
-                Seed::Literal {
-                    bytes: b"whitelist".to_vec(),
+        let account_meta = vec![
+            // Derive the mint's whitelist PDA for the source owner using our program ID
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"whitelist".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                    Seed::AccountKey { index: 3 },
+                ],
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            // Same for the destination owner, read from the owner field of the destination token account
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"whitelist".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                    Seed::AccountData {
+                        account_index: 2,
+                        data_index: 32,
+                        length: 32,
+                    },
+                ],
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            // The mint's config, holding the transfer policy
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"mint_config".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                ],
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            // The mint's registry of exempt authorities, may not exist
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"exempt_authorities".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                ],
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            // The mint's trusted attesters, may not exist
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"attesters".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                ],
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            // Credentials of the source and destination owners, may not exist
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"credential".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                    Seed::AccountKey { index: 3 },
+                ],
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"credential".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                    Seed::AccountData {
+                        account_index: 2,
+                        data_index: 32,
+                        length: 32,
+                    },
+                ],
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            // The destination owner itself, so the hook can see which program owns it
+            ExtraAccountMeta::new_with_pubkey_data(
+                &PubkeyData::AccountData {
+                    account_index: 2,
+                    data_index: 32,
+                },
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            // The mint's transfer log, written by the hook when the admin created it
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"transfer_log".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                ],
+                false, // is_signer
+                true,  // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            // The source token account owner with its whitelist entry and credential, which differ
+            // from the authority's when a delegate signs the transfer
+            ExtraAccountMeta::new_with_pubkey_data(
+                &PubkeyData::AccountData {
+                    account_index: 0,
+                    data_index: 32,
                 },
-                Seed::AccountKey { index: 3 },
-            ],
-            false, // is_signer
-            false, // is_writable
-        ).unwrap()];
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"whitelist".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                    Seed::AccountData {
+                        account_index: 0,
+                        data_index: 32,
+                        length: 32,
+                    },
+                ],
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"credential".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                    Seed::AccountData {
+                        account_index: 0,
+                        data_index: 32,
+                        length: 32,
+                    },
+                ],
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            // Multisig approvals of the authority, the destination owner and the source owner, may not exist
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"multisig".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                    Seed::AccountKey { index: 3 },
+                ],
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"multisig".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                    Seed::AccountData {
+                        account_index: 2,
+                        data_index: 32,
+                        length: 32,
+                    },
+                ],
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+            ExtraAccountMeta::new_with_seeds(
+                &[
+                    Seed::Literal {
+                        bytes: b"multisig".to_vec(),
+                    },
+                    Seed::AccountKey { index: 1 },
+                    Seed::AccountData {
+                        account_index: 0,
+                        data_index: 32,
+                        length: 32,
+                    },
+                ],
+                false, // is_signer
+                false, // is_writable
+            ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+        ];
         Ok(account_meta)
     }
-}
\ No newline at end of file
+
+    pub fn initialize_mint_config(&mut self, bumps: &InitializeExtraAccountMetaListBumps) -> Result<()> {
+        self.mint_config.set_inner(MintConfig {
+            mint: self.mint.key(),
+            admin: self.payer.key(),
+            policy: TransferPolicy::AllowlistSource,
+            paused: false,
+            recovery_wallet: None,
+            delegate_policy: DelegatePolicy::Both,
+            roles: Vec::new(),
+            bump: bumps.mint_config,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+use crate::state::Config;
+use crate::errors::WhitelistTransferHookError;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    // Created once per deployment, by the program's upgrade authority
+    #[account(
+        init,
+        payer = admin,
+        space = Config::INIT_SPACE + Config::DISCRIMINATOR.len(),
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+    // The program's ProgramData account, holding its upgrade authority
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConfig<'info> {
+    pub fn initialize_config(&mut self, bumps: InitializeConfigBumps) -> Result<()> {
+        self.config.set_inner(Config {
+            admin: self.admin.key(),
+            pending_admin: None,
+            bump: bumps.config,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::{MintConfig, TransferLog};
+use crate::errors::WhitelistTransferHookError;
+
+#[derive(Accounts)]
+pub struct InitializeTransferLog<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        has_one = admin @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    // From now on every hooked transfer of the mint is recorded here
+    #[account(
+        init,
+        payer = admin,
+        space = TransferLog::SPACE,
+        seeds = [b"transfer_log", mint.key().as_ref()],
+        bump
+    )]
+    pub transfer_log: AccountLoader<'info, TransferLog>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeTransferLog<'info> {
+    pub fn initialize_transfer_log(&mut self, bumps: &InitializeTransferLogBumps) -> Result<()> {
+        let mut transfer_log = self.transfer_log.load_init()?;
+        transfer_log.mint = self.mint.key();
+        transfer_log.bump = bumps.transfer_log;
+
+        msg!("Transfer log initialized: {}", self.transfer_log.key());
+        Ok(())
+    }
+}
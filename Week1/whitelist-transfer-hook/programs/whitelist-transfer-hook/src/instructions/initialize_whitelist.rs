@@ -1,17 +1,26 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
 
-use crate::state::Whitelist;
+use crate::state::{MintConfig, Role, Whitelist};
+use crate::errors::WhitelistTransferHookError;
 
 #[derive(Accounts)]
 #[instruction(user: Pubkey)]
 pub struct InitializeWhitelist<'info> {
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub operator: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        constraint = mint_config.has_role(Role::WhitelistOperator, &operator.key()) @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
     #[account(
         init,
-        payer = admin,
+        payer = operator,
         space = Whitelist::INIT_SPACE + Whitelist::DISCRIMINATOR.len(), // 8 bytes for discriminator,32 bytes for address, 4 bytes for vector length, 1 byte for bump
-        seeds = [b"whitelist", user.key().as_ref()],
+        seeds = [b"whitelist", mint.key().as_ref(), user.key().as_ref()],
         bump
     )]
     pub whitelist: Account<'info, Whitelist>,
@@ -25,9 +34,13 @@ impl<'info> InitializeWhitelist<'info> {
         user: Pubkey
     ) -> Result<()> {
         // Initialize the whitelist with an empty address vector
+        self.whitelist.mint = self.mint.key();
         self.whitelist.address = user.key();
         self.whitelist.bump = bumps.whitelist;
         self.whitelist.is_whitelisted = false;
+        self.whitelist.valid_from = 0;
+        self.whitelist.valid_until = None;
+        self.whitelist.revision = 0;
 
         Ok(())
     }
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::{DelegatePolicy, MintConfig, Role, RoleGrant, TransferPolicy};
+use crate::errors::WhitelistTransferHookError;
+
+#[derive(Accounts)]
+pub struct MintConfigOperations<'info> {
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        has_one = admin @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub pauser: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        constraint = mint_config.has_role(Role::Pauser, &pauser.key()) @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+}
+
+impl<'info> SetPaused<'info> {
+    pub fn set_paused(&mut self, paused: bool) -> Result<()> {
+        self.mint_config.paused = paused;
+        msg!("Transfers paused: {} by {}", paused, self.pauser.key());
+        Ok(())
+    }
+}
+
+impl<'info> MintConfigOperations<'info> {
+    pub fn set_policy(&mut self, policy: TransferPolicy) -> Result<()> {
+        self.mint_config.policy = policy;
+        msg!("Transfer policy set to {:?}", policy);
+        Ok(())
+    }
+
+    pub fn set_recovery_wallet(&mut self, recovery_wallet: Option<Pubkey>) -> Result<()> {
+        self.mint_config.recovery_wallet = recovery_wallet;
+        msg!("Recovery wallet set to {:?}", recovery_wallet);
+        Ok(())
+    }
+
+    pub fn set_delegate_policy(&mut self, delegate_policy: DelegatePolicy) -> Result<()> {
+        self.mint_config.delegate_policy = delegate_policy;
+        msg!("Delegate policy set to {:?}", delegate_policy);
+        Ok(())
+    }
+
+    pub fn grant_role(&mut self, role: Role, member: Pubkey) -> Result<()> {
+        let grant = RoleGrant { member, role };
+        require!(
+            !self.mint_config.roles.contains(&grant),
+            WhitelistTransferHookError::RoleAlreadyGranted
+        );
+        require!(
+            self.mint_config.roles.len() < MintConfig::MAX_ROLE_GRANTS,
+            WhitelistTransferHookError::RoleGrantsFull
+        );
+
+        self.mint_config.roles.push(grant);
+
+        msg!("Granted {:?} to {}", role, member);
+        Ok(())
+    }
+
+    pub fn revoke_role(&mut self, role: Role, member: Pubkey) -> Result<()> {
+        let index = self
+            .mint_config
+            .roles
+            .iter()
+            .position(|grant| grant == &RoleGrant { member, role })
+            .ok_or(WhitelistTransferHookError::RoleNotGranted)?;
+
+        self.mint_config.roles.swap_remove(index);
+
+        msg!("Revoked {:?} from {}", role, member);
+        Ok(())
+    }
+}
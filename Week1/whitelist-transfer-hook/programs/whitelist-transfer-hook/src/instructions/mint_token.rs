@@ -1,38 +1,152 @@
 use anchor_lang::{ 
     prelude::*, 
+    system_program::{transfer, Transfer},
 };
 use anchor_spl::token_interface::{
+    spl_token_metadata_interface::state::TokenMetadata,
+    token_metadata_initialize,
     Mint, 
-    TokenInterface,
+    Token2022,
+    TokenMetadataInitialize,
 };
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
 
-use crate::state::Whitelist;
+use crate::{
+    errors::WhitelistTransferHookError,
+    instructions::InitializeExtraAccountMetaList,
+    state::{Config, DelegatePolicy, MintConfig, TransferPolicy},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenMetadataArgs {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
 
 #[derive(Accounts)]
+#[instruction(decimals: u8)]
 pub struct TokenFactory<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
+    // The deployment admin approves every mint the hook serves
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    // The metadata pointer always targets the mint itself, metadata is only written when requested
     #[account(
         init,
         payer = user,
-        mint::decimals = 9,
+        mint::decimals = decimals,
         mint::authority = user,
+        mint::token_program = token_program,
+        extensions::transfer_hook::authority = user,
+        extensions::transfer_hook::program_id = crate::ID,
+        extensions::metadata_pointer::authority = user,
+        extensions::metadata_pointer::metadata_address = mint,
     )]
     pub mint: InterfaceAccount<'info, Mint>,
-    /// CHECK: ExtraAccountMetaList Account, will be checked by the transfer hook
-    #[account(mut)]
-    pub extra_account_meta_list: UncheckedAccount<'info>,
+    /// CHECK: ExtraAccountMetaList Account, must use these seeds
     #[account(
-        seeds = [b"whitelist"], 
+        init,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+        space = ExtraAccountMetaList::size_of(
+            InitializeExtraAccountMetaList::extra_account_metas()?.len()
+        ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?,
+        payer = user
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = user,
+        space = MintConfig::INIT_SPACE + MintConfig::DISCRIMINATOR.len(),
+        seeds = [b"mint_config", mint.key().as_ref()],
         bump
     )]
-    pub blocklist: Account<'info, Whitelist>,
+    pub mint_config: Account<'info, MintConfig>,
     pub system_program: Program<'info, System>,
-    pub token_program: Interface<'info, TokenInterface>,
+    pub token_program: Program<'info, Token2022>,
 }
 
 impl<'info> TokenFactory<'info> {
-    pub fn init_mint(&mut self, bumps: &TokenFactoryBumps) -> Result<()> {
+    pub fn init_mint(
+        &mut self,
+        metadata: Option<TokenMetadataArgs>,
+        bumps: &TokenFactoryBumps,
+    ) -> Result<()> {
+        // The issuer administers the whitelist of the mint it creates
+        self.mint_config.set_inner(MintConfig {
+            mint: self.mint.key(),
+            admin: self.user.key(),
+            policy: TransferPolicy::AllowlistSource,
+            paused: false,
+            recovery_wallet: None,
+            delegate_policy: DelegatePolicy::Both,
+            roles: Vec::new(),
+            bump: bumps.mint_config,
+        });
+
+        if let Some(metadata) = metadata {
+            self.init_metadata(metadata)?;
+        }
+
+        // Store the extra accounts the hook resolves, so the mint is transferable right away
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut self.extra_account_meta_list.try_borrow_mut_data()?,
+            &InitializeExtraAccountMetaList::extra_account_metas()?,
+        ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?;
+
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn init_metadata(&mut self, metadata: TokenMetadataArgs) -> Result<()> {
+        // The token program reallocs the mint for the metadata, but the rent has to be there first
+        let metadata_len = TokenMetadata {
+            name: metadata.name.clone(),
+            symbol: metadata.symbol.clone(),
+            uri: metadata.uri.clone(),
+            ..Default::default()
+        }
+        .tlv_size_of()?;
+
+        let mint_info = self.mint.to_account_info();
+        let lamports = Rent::get()?
+            .minimum_balance(mint_info.data_len() + metadata_len)
+            .saturating_sub(mint_info.lamports());
+
+        if lamports > 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.user.to_account_info(),
+                        to: mint_info.clone(),
+                    },
+                ),
+                lamports,
+            )?;
+        }
+
+        token_metadata_initialize(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TokenMetadataInitialize {
+                    program_id: self.token_program.to_account_info(),
+                    mint: mint_info.clone(),
+                    metadata: mint_info,
+                    mint_authority: self.user.to_account_info(),
+                    update_authority: self.user.to_account_info(),
+                },
+            ),
+            metadata.name,
+            metadata.symbol,
+            metadata.uri,
+        )
+    }
+}
@@ -1,12 +1,32 @@
 pub mod init_extra_account_meta;
+pub mod update_extra_account_meta;
 pub mod transfer_hook;
+pub mod initialize_config;
+pub mod admin_operations;
+pub mod mint_config_operations;
 pub mod initialize_whitelist;
 pub mod whitelist_operations;
+pub mod batch_whitelist_operations;
+pub mod close_whitelist_entry;
+pub mod exempt_authority_operations;
+pub mod attestation_operations;
+pub mod initialize_transfer_log;
+pub mod multisig_operations;
 pub mod mint_token;
 
 
 pub use init_extra_account_meta::*;
+pub use update_extra_account_meta::*;
 pub use transfer_hook::*;
+pub use initialize_config::*;
+pub use admin_operations::*;
+pub use mint_config_operations::*;
 pub use initialize_whitelist::*;
 pub use whitelist_operations::*;
-pub use mint_token::*;
\ No newline at end of file
+pub use batch_whitelist_operations::*;
+pub use close_whitelist_entry::*;
+pub use exempt_authority_operations::*;
+pub use attestation_operations::*;
+pub use initialize_transfer_log::*;
+pub use multisig_operations::*;
+pub use mint_token::*;
@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::{EntryStatus, MintConfig, MultisigApproval, Role, Whitelist};
+use crate::errors::WhitelistTransferHookError;
+
+/// Approves a multisig as a whitelisted owner. When signers must be whitelisted, the whitelist PDAs
+/// of all its signers are passed, in the multisig's signer order, as remaining accounts. Such an
+/// approval ends when the first signer entry expires or once it is lapsed, and must then be
+/// approved again.
+#[derive(Accounts)]
+pub struct ApproveMultisig<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        constraint = mint_config.has_role(Role::WhitelistOperator, &operator.key()) @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    /// CHECK: Read as a token-2022 multisig in the instruction
+    pub multisig: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = operator,
+        space = MultisigApproval::INIT_SPACE + MultisigApproval::DISCRIMINATOR.len(),
+        seeds = [b"multisig", mint.key().as_ref(), multisig.key().as_ref()],
+        bump
+    )]
+    pub multisig_approval: Account<'info, MultisigApproval>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ApproveMultisig<'info> {
+    pub fn approve_multisig(
+        &mut self,
+        bumps: &ApproveMultisigBumps,
+        require_whitelisted_signers: bool,
+        signer_whitelists: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let (m, signers) = MultisigApproval::read_multisig(&self.multisig)?
+            .ok_or(WhitelistTransferHookError::NotAMultisig)?;
+
+        // The hook never sees which signers approved a transfer, so every signer that could be part
+        // of a quorum must be whitelisted
+        let mut signer_revisions = Vec::new();
+        let mut signers_valid_from = 0;
+        let mut signers_valid_until = None;
+        if require_whitelisted_signers {
+            require_eq!(
+                signers.len(),
+                signer_whitelists.len(),
+                WhitelistTransferHookError::BatchLengthMismatch
+            );
+
+            let mint = self.mint.key();
+            let now = Clock::get()?.unix_timestamp;
+            for (signer, info) in signers.iter().zip(signer_whitelists) {
+                let (address, _) =
+                    Pubkey::find_program_address(&[b"whitelist", mint.as_ref(), signer.as_ref()], &crate::ID);
+                require_keys_eq!(info.key(), address, WhitelistTransferHookError::InvalidWhitelistAccount);
+                require!(
+                    Whitelist::status(info, now)? == Some(EntryStatus::Whitelisted),
+                    WhitelistTransferHookError::SignerNotWhitelisted
+                );
+
+                // The approval only holds while every signer's entry does
+                let entry = Whitelist::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+                signer_revisions.push(entry.revision);
+                signers_valid_from = signers_valid_from.max(entry.valid_from);
+                signers_valid_until = match (signers_valid_until, entry.valid_until) {
+                    (Some(a), Some(b)) => Some(std::cmp::min(a, b)),
+                    (a, b) => a.or(b),
+                };
+            }
+        }
+
+        self.multisig_approval.set_inner(MultisigApproval {
+            mint: self.mint.key(),
+            multisig: self.multisig.key(),
+            m,
+            signers,
+            signers_whitelisted: require_whitelisted_signers,
+            signer_revisions,
+            signers_valid_from,
+            signers_valid_until,
+            lapsed: false,
+            bump: bumps.multisig_approval,
+        });
+
+        msg!("Multisig {} approved as {}-of-{}", self.multisig.key(), m, self.multisig_approval.signers.len());
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RevokeMultisig<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        constraint = mint_config.has_role(Role::WhitelistOperator, &operator.key()) @ WhitelistTransferHookError::Unauthorized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    #[account(
+        mut,
+        close = operator,
+        seeds = [b"multisig", mint.key().as_ref(), multisig_approval.multisig.as_ref()],
+        bump = multisig_approval.bump,
+    )]
+    pub multisig_approval: Account<'info, MultisigApproval>,
+}
+
+impl<'info> RevokeMultisig<'info> {
+    pub fn revoke_multisig(&mut self) -> Result<()> {
+        msg!("Multisig {} no longer approved", self.multisig_approval.multisig);
+        Ok(())
+    }
+}
+
+/// Lapses an approval that requires whitelisted signers once one of their entries was removed,
+/// closed or given a new window since. Anyone may call it; operators weakening a signer's entry
+/// send it in the same transaction.
+#[derive(Accounts)]
+#[instruction(signer: Pubkey)]
+pub struct LapseMultisigApproval<'info> {
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"multisig", mint.key().as_ref(), multisig_approval.multisig.as_ref()],
+        bump = multisig_approval.bump,
+    )]
+    pub multisig_approval: Account<'info, MultisigApproval>,
+    /// CHECK: The signer's whitelist PDA, which may have been closed
+    #[account(
+        seeds = [b"whitelist", mint.key().as_ref(), signer.as_ref()],
+        bump,
+    )]
+    pub signer_whitelist: UncheckedAccount<'info>,
+}
+
+impl<'info> LapseMultisigApproval<'info> {
+    pub fn lapse_multisig_approval(&mut self, signer: Pubkey) -> Result<()> {
+        let index = self
+            .multisig_approval
+            .signers
+            .iter()
+            .position(|key| key == &signer)
+            .ok_or(WhitelistTransferHookError::NotAMultisigSigner)?;
+
+        // Approvals that never checked their signers have no revisions to compare
+        let recorded = self
+            .multisig_approval
+            .signer_revisions
+            .get(index)
+            .copied()
+            .ok_or(WhitelistTransferHookError::SignerEntryUnchanged)?;
+
+        let info = self.signer_whitelist.to_account_info();
+        let changed = if info.owner != &crate::ID || info.data_is_empty() {
+            true
+        } else {
+            let entry = Whitelist::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            entry.revision != recorded || !entry.is_whitelisted
+        };
+        require!(changed, WhitelistTransferHookError::SignerEntryUnchanged);
+
+        self.multisig_approval.lapsed = true;
+        msg!("Approval of multisig {} lapsed, entry of signer {} changed", self.multisig_approval.multisig, signer);
+        Ok(())
+    }
+}
@@ -16,13 +16,18 @@ use anchor_spl::{
     }
 };
 
-use crate::state::Whitelist;
+use crate::events::TransferChecked;
+use crate::state::{
+    Attesters, Credential, DelegatePolicy, EntryStatus, ExemptAuthorities, MintConfig, MultisigApproval,
+    TransferDecision, TransferLog, TransferRecord, Whitelist,
+};
+use crate::errors::WhitelistTransferHookError;
 
 #[derive(Accounts)]
 pub struct TransferHook<'info> {
+    // The authority is checked by the token program, it may be the owner or a delegate
     #[account(
         token::mint = mint, 
-        token::authority = owner,
     )]
     pub source_token: InterfaceAccount<'info, TokenAccount>,
     pub mint: InterfaceAccount<'info, Mint>,
@@ -30,7 +35,7 @@ pub struct TransferHook<'info> {
         token::mint = mint,
     )]
     pub destination_token: InterfaceAccount<'info, TokenAccount>,
-    /// CHECK: source token account owner, can be SystemAccount or PDA owned by another program
+    /// CHECK: transfer authority, the source token account owner or a delegate. Can be SystemAccount or PDA owned by another program
     pub owner: UncheckedAccount<'info>,
     /// CHECK: ExtraAccountMetaList Account,
     #[account(
@@ -38,16 +43,99 @@ pub struct TransferHook<'info> {
         bump
     )]
     pub extra_account_meta_list: UncheckedAccount<'info>,
+    /// CHECK: Source owner's whitelist entry for this mint, resolved from account indexes 1 and 3 by the extra account metas, may not exist
+    #[account(
+        seeds = [b"whitelist", mint.key().as_ref(), owner.key().as_ref()], 
+        bump,
+    )]
+    pub whitelist: UncheckedAccount<'info>,
+    /// CHECK: Destination owner's whitelist entry for this mint, may not exist
+    #[account(
+        seeds = [b"whitelist", mint.key().as_ref(), destination_token.owner.as_ref()], 
+        bump,
+    )]
+    pub destination_whitelist: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"mint_config", mint.key().as_ref()], 
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    /// CHECK: Registry of exempt authorities for this mint, may not exist
+    #[account(
+        seeds = [b"exempt_authorities", mint.key().as_ref()], 
+        bump,
+    )]
+    pub exempt_authorities: UncheckedAccount<'info>,
+    /// CHECK: Trusted attesters for this mint, may not exist
+    #[account(
+        seeds = [b"attesters", mint.key().as_ref()], 
+        bump,
+    )]
+    pub attesters: UncheckedAccount<'info>,
+    /// CHECK: Source owner's credential for this mint, may not exist
+    #[account(
+        seeds = [b"credential", mint.key().as_ref(), owner.key().as_ref()], 
+        bump,
+    )]
+    pub credential: UncheckedAccount<'info>,
+    /// CHECK: Destination owner's credential for this mint, may not exist
+    #[account(
+        seeds = [b"credential", mint.key().as_ref(), destination_token.owner.as_ref()], 
+        bump,
+    )]
+    pub destination_credential: UncheckedAccount<'info>,
+    /// CHECK: destination token account owner, read when it is a multisig
+    #[account(
+        address = destination_token.owner,
+    )]
+    pub destination_owner: UncheckedAccount<'info>,
+    /// CHECK: Transfer log for this mint, may not exist
+    #[account(
+        mut,
+        seeds = [b"transfer_log", mint.key().as_ref()], 
+        bump,
+    )]
+    pub transfer_log: UncheckedAccount<'info>,
+    /// CHECK: source token account owner, the same account as `owner` unless a delegate signs
+    #[account(
+        address = source_token.owner,
+    )]
+    pub source_owner: UncheckedAccount<'info>,
+    /// CHECK: Source token account owner's whitelist entry for this mint, may not exist
+    #[account(
+        seeds = [b"whitelist", mint.key().as_ref(), source_token.owner.as_ref()], 
+        bump,
+    )]
+    pub source_owner_whitelist: UncheckedAccount<'info>,
+    /// CHECK: Source token account owner's credential for this mint, may not exist
     #[account(
-        seeds = [b"whitelist"], 
-        bump = whitelist.bump,
+        seeds = [b"credential", mint.key().as_ref(), source_token.owner.as_ref()], 
+        bump,
     )]
-    pub whitelist: Account<'info, Whitelist>,
+    pub source_owner_credential: UncheckedAccount<'info>,
+    /// CHECK: Multisig approval of the transfer authority for this mint, may not exist
+    #[account(
+        seeds = [b"multisig", mint.key().as_ref(), owner.key().as_ref()], 
+        bump,
+    )]
+    pub multisig_approval: UncheckedAccount<'info>,
+    /// CHECK: Multisig approval of the destination owner for this mint, may not exist
+    #[account(
+        seeds = [b"multisig", mint.key().as_ref(), destination_token.owner.as_ref()], 
+        bump,
+    )]
+    pub destination_multisig_approval: UncheckedAccount<'info>,
+    /// CHECK: Multisig approval of the source token account owner for this mint, may not exist
+    #[account(
+        seeds = [b"multisig", mint.key().as_ref(), source_token.owner.as_ref()], 
+        bump,
+    )]
+    pub source_owner_multisig_approval: UncheckedAccount<'info>,
 }
 
 impl<'info> TransferHook<'info> {
     /// This function is called when the transfer hook is executed.
-    pub fn transfer_hook(&mut self, _amount: u64) -> Result<()> {
+    pub fn transfer_hook(&mut self, amount: u64) -> Result<()> {
         // Fail this instruction if it is not called from within a transfer hook
         
         self.check_is_transferring()?;
@@ -55,13 +143,133 @@ impl<'info> TransferHook<'info> {
         msg!("Source token owner: {}", self.source_token.owner);
         msg!("Destination token owner: {}", self.destination_token.owner);
 
-        if self.whitelist.is_whitelisted{
-            msg!("Transfer allowed: The address is whitelisted");
+        if self.mint_config.paused {
+            require!(
+                self.mint_config.is_recovery_transfer(&self.source_token.owner, &self.destination_token.owner),
+                WhitelistTransferHookError::TransferPaused
+            );
+            msg!("Transfer allowed while paused: recovery wallet involved");
+            return self.record(amount, TransferDecision::Recovery, None, None);
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let exempt_authorities = ExemptAuthorities::load(&self.exempt_authorities)?;
+        let attesters = Attesters::load(&self.attesters)?;
+
+        let authority = Self::owner_status(
+            &self.owner,
+            &self.whitelist,
+            &self.credential,
+            &self.multisig_approval,
+            &exempt_authorities,
+            attesters.as_ref(),
+            now,
+        )?;
+        let source = if self.owner.key() == self.source_token.owner {
+            authority
         } else {
-            panic!("TransferHook: Address is not whitelisted");
+            // A delegate (or permanent delegate) signed, the policy decides whose entry counts
+            let owner = Self::owner_status(
+                &self.source_owner,
+                &self.source_owner_whitelist,
+                &self.source_owner_credential,
+                &self.source_owner_multisig_approval,
+                &exempt_authorities,
+                attesters.as_ref(),
+                now,
+            )?;
+            msg!("Delegated transfer by {}, policy {:?}", self.owner.key(), self.mint_config.delegate_policy);
+            match self.mint_config.delegate_policy {
+                DelegatePolicy::Delegate => authority,
+                DelegatePolicy::Owner => owner,
+                DelegatePolicy::Both => EntryStatus::stricter(authority, owner),
+            }
+        };
+        let destination = Self::owner_status(
+            &self.destination_owner,
+            &self.destination_whitelist,
+            &self.destination_credential,
+            &self.destination_multisig_approval,
+            &exempt_authorities,
+            attesters.as_ref(),
+            now,
+        )?;
+
+        self.mint_config.check(source, destination)?;
+        msg!("Transfer allowed by policy {:?}", self.mint_config.policy);
+
+        self.record(amount, TransferDecision::Policy(self.mint_config.policy), source, destination)
+    }
+
+    /// Emits the audit event of an allowed transfer and appends it to the mint's transfer log
+    fn record(
+        &self,
+        amount: u64,
+        decision: TransferDecision,
+        source: Option<EntryStatus>,
+        destination: Option<EntryStatus>,
+    ) -> Result<()> {
+        let event = TransferChecked {
+            mint: self.mint.key(),
+            source: self.source_token.key(),
+            destination: self.destination_token.key(),
+            source_owner: self.source_token.owner,
+            destination_owner: self.destination_token.owner,
+            amount,
+            decision,
+            source_whitelisted: source == Some(EntryStatus::Whitelisted),
+            destination_whitelisted: destination == Some(EntryStatus::Whitelisted),
+            slot: Clock::get()?.slot,
+        };
+        let record = TransferRecord {
+            source: event.source,
+            destination: event.destination,
+            source_owner: event.source_owner,
+            destination_owner: event.destination_owner,
+            amount: event.amount,
+            slot: event.slot,
+            decision: event.decision.to_code(),
+            source_whitelisted: event.source_whitelisted.into(),
+            destination_whitelisted: event.destination_whitelisted.into(),
+            padding: [0; 5],
+        };
+
+        emit!(event);
+
+        TransferLog::append(&self.transfer_log, record)
+    }
+
+    /// Resolves how the policy sees one side of the transfer
+    fn owner_status(
+        owner: &AccountInfo,
+        whitelist: &AccountInfo,
+        credential: &AccountInfo,
+        multisig_approval: &AccountInfo,
+        exempt_authorities: &[Pubkey],
+        attesters: Option<&Attesters>,
+        now: i64,
+    ) -> Result<Option<EntryStatus>> {
+        // Registered PDAs (escrows, pool authorities) count as whitelisted. The account's owner is not
+        // enough, any program can derive a PDA, assign it to an exempt program and still sign for it.
+        if exempt_authorities.contains(owner.key) {
+            return Ok(Some(EntryStatus::Whitelisted));
         }
 
-        Ok(())
+        // An explicit entry decides, except that an approved multisig or a valid credential stands
+        // in for a missing or expired one. An entry the admin set to not whitelisted is never overridden.
+        let entry = Whitelist::status(whitelist, now)?;
+        if matches!(entry, Some(EntryStatus::Whitelisted) | Some(EntryStatus::NotWhitelisted)) {
+            return Ok(entry);
+        }
+        if MultisigApproval::covers(multisig_approval, owner, now)? {
+            return Ok(Some(EntryStatus::Whitelisted));
+        }
+        match attesters {
+            Some(attesters) if Credential::is_valid(credential, attesters, now)? => {
+                Ok(Some(EntryStatus::Whitelisted))
+            }
+            _ => Ok(entry),
+        }
     }
 
     /// Checks if the transfer hook is being executed during a transfer operation.
@@ -83,9 +291,10 @@ impl<'info> TransferHook<'info> {
         let account_extension = account.get_extension_mut::<TransferHookAccount>()?;
     
         // Check if the account is in the middle of a transfer operation
-        if !bool::from(account_extension.transferring) {
-            panic!("TransferHook: Not transferring");
-        }
+        require!(
+            bool::from(account_extension.transferring),
+            WhitelistTransferHookError::NotTransferring
+        );
     
         Ok(())
     }
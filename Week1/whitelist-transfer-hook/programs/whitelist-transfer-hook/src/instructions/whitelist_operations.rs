@@ -1,23 +1,25 @@
-use anchor_lang::{
-    prelude::*, 
-    system_program
-};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
 
-use crate::state::whitelist::Whitelist;
+use crate::state::{MintConfig, Role, Whitelist};
 use crate::errors::WhitelistTransferHookError;
 
 
 #[derive(Accounts)]
 pub struct WhitelistOperations<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
-        mut,
-        //address = 
+        seeds = [b"mint_config", mint.key().as_ref()],
+        bump = mint_config.bump,
+        constraint = mint_config.has_role(Role::WhitelistOperator, &operator.key()) @ WhitelistTransferHookError::Unauthorized,
     )]
-    pub admin: Signer<'info>,
+    pub mint_config: Account<'info, MintConfig>,
     #[account(
         mut,
-        seeds = [b"whitelist", user.key().as_ref()],
-        bump,
+        seeds = [b"whitelist", mint.key().as_ref(), user.key().as_ref()],
+        bump = whitelist.bump,
     )]
     pub whitelist: Account<'info, Whitelist>,
     #[account(mut)]
@@ -31,15 +33,29 @@ impl<'info> WhitelistOperations<'info> {
             Err(WhitelistTransferHookError::AlreadyWhitelisted)?
         }
         self.whitelist.is_whitelisted = true;
+        msg!("Added to whitelist: {}", user);
         Ok(())
     }
 
-    pub fn remove_from_whitelist(&mut self, address: Pubkey) -> Result<()> {
+    pub fn remove_from_whitelist(&mut self, user: Pubkey) -> Result<()> {
         if !self.whitelist.is_whitelisted {
             Err(WhitelistTransferHookError::NotWhitelisted)?
         }
         self.whitelist.is_whitelisted = false;
+        self.whitelist.note_change();
+        msg!("Removed from whitelist: {}", user);
         Ok(())
     }
 
-}
\ No newline at end of file
+    pub fn set_validity(&mut self, user: Pubkey, valid_from: i64, valid_until: Option<i64>) -> Result<()> {
+        if let Some(until) = valid_until {
+            require!(until > valid_from, WhitelistTransferHookError::InvalidValidityWindow);
+        }
+        self.whitelist.valid_from = valid_from;
+        self.whitelist.valid_until = valid_until;
+        self.whitelist.note_change();
+        msg!("Whitelist validity of {} set to {}..{:?}", user, valid_from, valid_until);
+        Ok(())
+    }
+
+}
@@ -3,19 +3,18 @@
 
 use anchor_lang::prelude::*;
 
-mod instructions;
-mod state;
-mod errors;
+pub mod instructions;
+pub mod state;
+pub mod errors;
+pub mod events;
+#[cfg(test)]
+mod tests;
 
 use instructions::*;
+use state::{DelegatePolicy, Role, TransferPolicy};
 use errors::*;
 use spl_discriminator::SplDiscriminate;
-use spl_transfer_hook_interface::{
-    instruction::{
-        ExecuteInstruction, 
-        InitializeExtraAccountMetaListInstruction
-    },
-};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
 use spl_tlv_account_resolution::state::ExtraAccountMetaList;
 
 declare_id!("DhzyDgCmmQzVC4vEcj2zRGUyN8Mt5JynfdGLKkBcRGaX");
@@ -24,6 +23,95 @@ declare_id!("DhzyDgCmmQzVC4vEcj2zRGUyN8Mt5JynfdGLKkBcRGaX");
 pub mod whitelist_transfer_hook {
     use super::*;
 
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        ctx.accounts.initialize_config(ctx.bumps)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.propose_admin(new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        ctx.accounts.accept_admin()
+    }
+
+    pub fn set_mint_admin(ctx: Context<SetMintAdmin>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.set_mint_admin(new_admin)
+    }
+
+    pub fn set_policy(ctx: Context<MintConfigOperations>, policy: TransferPolicy) -> Result<()> {
+        ctx.accounts.set_policy(policy)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.set_paused(paused)
+    }
+
+    pub fn set_recovery_wallet(
+        ctx: Context<MintConfigOperations>,
+        recovery_wallet: Option<Pubkey>
+    ) -> Result<()> {
+        ctx.accounts.set_recovery_wallet(recovery_wallet)
+    }
+
+    pub fn set_delegate_policy(
+        ctx: Context<MintConfigOperations>,
+        delegate_policy: DelegatePolicy
+    ) -> Result<()> {
+        ctx.accounts.set_delegate_policy(delegate_policy)
+    }
+
+    pub fn grant_role(ctx: Context<MintConfigOperations>, role: Role, member: Pubkey) -> Result<()> {
+        ctx.accounts.grant_role(role, member)
+    }
+
+    pub fn revoke_role(ctx: Context<MintConfigOperations>, role: Role, member: Pubkey) -> Result<()> {
+        ctx.accounts.revoke_role(role, member)
+    }
+
+    pub fn add_exempt_authority(
+        ctx: Context<ExemptAuthorityOperations>,
+        authority: Pubkey
+    ) -> Result<()> {
+        ctx.accounts.add_exempt_authority(&ctx.bumps, authority)
+    }
+
+    pub fn remove_exempt_authority(
+        ctx: Context<ExemptAuthorityOperations>,
+        authority: Pubkey
+    ) -> Result<()> {
+        ctx.accounts.remove_exempt_authority(authority)
+    }
+
+    pub fn add_attester(ctx: Context<AttesterOperations>, attester: Pubkey) -> Result<()> {
+        ctx.accounts.add_attester(&ctx.bumps, attester)
+    }
+
+    pub fn remove_attester(ctx: Context<AttesterOperations>, attester: Pubkey) -> Result<()> {
+        ctx.accounts.remove_attester(attester)
+    }
+
+    pub fn set_required_claim(ctx: Context<AttesterOperations>, claim: u16) -> Result<()> {
+        ctx.accounts.set_required_claim(&ctx.bumps, claim)
+    }
+
+    pub fn issue_credential(
+        ctx: Context<IssueCredential>,
+        subject: Pubkey,
+        claim: u16,
+        expires_at: i64
+    ) -> Result<()> {
+        ctx.accounts.issue_credential(&ctx.bumps, subject, claim, expires_at)
+    }
+
+    pub fn revoke_credential(ctx: Context<RevokeCredential>, subject: Pubkey) -> Result<()> {
+        ctx.accounts.revoke_credential(subject)
+    }
+
+    pub fn initialize_transfer_log(ctx: Context<InitializeTransferLog>) -> Result<()> {
+        ctx.accounts.initialize_transfer_log(&ctx.bumps)
+    }
+
     pub fn initialize_whitelist(
         ctx: Context<InitializeWhitelist>,
         user: Pubkey
@@ -45,6 +133,54 @@ pub mod whitelist_transfer_hook {
         ctx.accounts.remove_from_whitelist(user)
     }
 
+    pub fn batch_add_to_whitelist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchWhitelistOperations<'info>>,
+        users: Vec<Pubkey>
+    ) -> Result<()> {
+        ctx.accounts.batch_add_to_whitelist(users, ctx.remaining_accounts)
+    }
+
+    pub fn batch_remove_from_whitelist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchWhitelistOperations<'info>>,
+        users: Vec<Pubkey>
+    ) -> Result<()> {
+        ctx.accounts.batch_remove_from_whitelist(users, ctx.remaining_accounts)
+    }
+
+    pub fn approve_multisig<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApproveMultisig<'info>>,
+        require_whitelisted_signers: bool,
+    ) -> Result<()> {
+        ctx.accounts.approve_multisig(&ctx.bumps, require_whitelisted_signers, ctx.remaining_accounts)
+    }
+
+    pub fn revoke_multisig(ctx: Context<RevokeMultisig>) -> Result<()> {
+        ctx.accounts.revoke_multisig()
+    }
+
+    pub fn lapse_multisig_approval(
+        ctx: Context<LapseMultisigApproval>,
+        signer: Pubkey
+    ) -> Result<()> {
+        ctx.accounts.lapse_multisig_approval(signer)
+    }
+
+    pub fn close_whitelist_entry(
+        ctx: Context<CloseWhitelistEntry>,
+        user: Pubkey
+    ) -> Result<()> {
+        ctx.accounts.close_whitelist_entry(user)
+    }
+
+    pub fn set_whitelist_validity(
+        ctx: Context<WhitelistOperations>,
+        user: Pubkey,
+        valid_from: i64,
+        valid_until: Option<i64>
+    ) -> Result<()> {
+        ctx.accounts.set_validity(user, valid_from, valid_until)
+    }
+
 
     pub fn initialize_transfer_hook(
         ctx: Context<InitializeExtraAccountMetaList>
@@ -52,6 +188,8 @@ pub mod whitelist_transfer_hook {
 
         msg!("Initializing Transfer Hook...");
 
+        ctx.accounts.initialize_mint_config(&ctx.bumps)?;
+
         // Get the extra account metas for the transfer hook
         let extra_account_metas = InitializeExtraAccountMetaList::extra_account_metas()?;
 
@@ -62,11 +200,24 @@ pub mod whitelist_transfer_hook {
         ExtraAccountMetaList::init::<ExecuteInstruction>(
             &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
             &extra_account_metas
-        ).unwrap();
+        ).map_err(|_| error!(WhitelistTransferHookError::InvalidExtraAccountMetas))?;
 
         Ok(())
     }
 
+    pub fn init_mint(
+        ctx: Context<TokenFactory>,
+        _decimals: u8,
+        metadata: Option<TokenMetadataArgs>
+    ) -> Result<()> {
+        // Decimals are consumed by the mint init constraint
+        ctx.accounts.init_mint(metadata, &ctx.bumps)
+    }
+
+    pub fn update_extra_account_metas(ctx: Context<UpdateExtraAccountMetaList>) -> Result<()> {
+        ctx.accounts.update_extra_account_metas()
+    }
+
     #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
     pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
         // Call the transfer hook logic
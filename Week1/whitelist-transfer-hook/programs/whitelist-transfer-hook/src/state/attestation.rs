@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Attesters {
+    pub mint: Pubkey,
+    // Keys (or program PDAs) trusted to issue credentials for this mint
+    #[max_len(8)]
+    pub attesters: Vec<Pubkey>,
+    // Claim a credential must carry to count as whitelisted, e.g. a KYC level
+    pub required_claim: u16,
+    pub bump: u8,
+}
+
+impl Attesters {
+    pub const MAX_ATTESTERS: usize = 8;
+
+    /// Reads the registry resolved by the hook, `None` if the admin never registered an attester
+    pub fn load(info: &AccountInfo) -> Result<Option<Attesters>> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Attesters::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Credential {
+    pub mint: Pubkey,
+    pub subject: Pubkey,
+    pub issuer: Pubkey,
+    pub claim: u16,
+    pub expires_at: i64,
+    pub revoked: bool,
+    pub bump: u8,
+}
+
+impl Credential {
+    /// Whether the credential resolved by the hook lets its subject transfer: issued by an attester
+    /// that is still trusted, for the required claim, not revoked and not expired
+    pub fn is_valid(info: &AccountInfo, attesters: &Attesters, now: i64) -> Result<bool> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(false);
+        }
+
+        let credential = Credential::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+
+        Ok(attesters.attesters.contains(&credential.issuer)
+            && credential.claim == attesters.required_claim
+            && !credential.revoked
+            && now < credential.expires_at)
+    }
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ExemptAuthorities {
+    pub mint: Pubkey,
+    // PDAs of DeFi programs (escrows, pool authorities, ...) that may hold and move the mint without
+    // a whitelist entry. Exact addresses, owning a PDA does not prove which program derived it.
+    #[max_len(16)]
+    pub authorities: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl ExemptAuthorities {
+    pub const MAX_AUTHORITIES: usize = 16;
+
+    /// Reads the registry resolved by the hook, empty if the admin never registered an authority
+    pub fn load(info: &AccountInfo) -> Result<Vec<Pubkey>> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let exempt_authorities = ExemptAuthorities::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(exempt_authorities.authorities)
+    }
+}
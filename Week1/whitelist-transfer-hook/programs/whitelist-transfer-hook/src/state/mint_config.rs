@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::WhitelistTransferHookError;
+use crate::state::EntryStatus;
+
+/// Rule the transfer hook applies to the source and destination owners' entries
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum TransferPolicy {
+    /// The source owner must be whitelisted
+    AllowlistSource,
+    /// Both the source and destination owners must be whitelisted
+    AllowlistBoth,
+    /// Either the source or the destination owner must be whitelisted
+    AllowlistEither,
+    /// Anyone may transfer unless one side has an entry that is not whitelisted
+    Blocklist,
+    /// Entries are ignored
+    Open,
+}
+
+/// Whose entry stands for the source side when a delegate, rather than the owner, signs a transfer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum DelegatePolicy {
+    /// The delegate moving the tokens
+    Delegate,
+    /// The owner of the source token account
+    Owner,
+    /// Both the delegate and the owner
+    Both,
+}
+
+/// Permission the mint admin can grant to other keys. The admin itself holds every role.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum Role {
+    /// May create, update and close whitelist entries
+    WhitelistOperator,
+    /// May pause and unpause transfers
+    Pauser,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct RoleGrant {
+    pub member: Pubkey,
+    pub role: Role,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct MintConfig {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+    pub policy: TransferPolicy,
+    pub paused: bool,
+    // Still allowed to send or receive while paused, to move funds during an incident
+    pub recovery_wallet: Option<Pubkey>,
+    pub delegate_policy: DelegatePolicy,
+    #[max_len(16)]
+    pub roles: Vec<RoleGrant>,
+    pub bump: u8,
+}
+
+impl MintConfig {
+    pub const MAX_ROLE_GRANTS: usize = 16;
+
+    /// Whether `member` may act in `role`, the admin acting as super-admin for every role
+    pub fn has_role(&self, role: Role, member: &Pubkey) -> bool {
+        &self.admin == member || self.roles.contains(&RoleGrant { member: *member, role })
+    }
+
+    /// Whether a paused mint still lets this transfer through, because the recovery wallet is involved
+    pub fn is_recovery_transfer(&self, source_owner: &Pubkey, destination_owner: &Pubkey) -> bool {
+        self.recovery_wallet
+            .is_some_and(|wallet| &wallet == source_owner || &wallet == destination_owner)
+    }
+
+    /// Checks a transfer against the policy, given the source and destination entries
+    /// (`None` when the owner has no entry for this mint)
+    pub fn check(&self, source: Option<EntryStatus>, destination: Option<EntryStatus>) -> Result<()> {
+        let source_allowed = source == Some(EntryStatus::Whitelisted);
+        let destination_allowed = destination == Some(EntryStatus::Whitelisted);
+
+        match self.policy {
+            TransferPolicy::AllowlistSource => {
+                require!(source_allowed, Self::rejection(source, WhitelistTransferHookError::NotWhitelisted));
+            }
+            TransferPolicy::AllowlistBoth => {
+                require!(source_allowed, Self::rejection(source, WhitelistTransferHookError::NotWhitelisted));
+                require!(
+                    destination_allowed,
+                    Self::rejection(destination, WhitelistTransferHookError::DestinationNotWhitelisted)
+                );
+            }
+            TransferPolicy::AllowlistEither => {
+                require!(
+                    source_allowed || destination_allowed,
+                    Self::rejection(source, WhitelistTransferHookError::NotWhitelisted)
+                );
+            }
+            TransferPolicy::Blocklist => {
+                for entry in [source, destination] {
+                    match entry {
+                        Some(EntryStatus::NotWhitelisted) => {
+                            return err!(WhitelistTransferHookError::Blocklisted)
+                        }
+                        Some(EntryStatus::OutsideWindow) => {
+                            return err!(WhitelistTransferHookError::OutsideValidityWindow)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            TransferPolicy::Open => {}
+        }
+
+        Ok(())
+    }
+
+    // Expired or not yet valid entries get their own error, so holders know to renew
+    fn rejection(entry: Option<EntryStatus>, otherwise: WhitelistTransferHookError) -> WhitelistTransferHookError {
+        match entry {
+            Some(EntryStatus::OutsideWindow) => WhitelistTransferHookError::OutsideValidityWindow,
+            _ => otherwise,
+        }
+    }
+}
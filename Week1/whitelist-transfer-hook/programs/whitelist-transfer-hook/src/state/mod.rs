@@ -1,3 +1,15 @@
+pub mod attestation;
+pub mod config;
+pub mod exempt_authorities;
+pub mod mint_config;
+pub mod multisig_approval;
+pub mod transfer_log;
 pub mod whitelist;
 
-pub use whitelist::*;
\ No newline at end of file
+pub use attestation::*;
+pub use config::*;
+pub use exempt_authorities::*;
+pub use mint_config::*;
+pub use multisig_approval::*;
+pub use transfer_log::*;
+pub use whitelist::*;
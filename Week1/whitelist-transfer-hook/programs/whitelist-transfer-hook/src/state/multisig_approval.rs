@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token_2022::spl_token_2022::{self, instruction::MAX_SIGNERS, state::Multisig};
+
+/// Whitelists a token-2022 multisig by its m-of-n configuration
+#[account]
+#[derive(InitSpace)]
+pub struct MultisigApproval {
+    pub mint: Pubkey,
+    pub multisig: Pubkey,
+    pub m: u8,
+    #[max_len(11)]
+    pub signers: Vec<Pubkey>,
+    // Every signer held a whitelisted entry when the approval was recorded
+    pub signers_whitelisted: bool,
+    // When signers must be whitelisted: the revision of each signer's entry at approval time, in
+    // signer order, and the window in which every signer's entry is valid. The hook cannot read
+    // the signers' entries, so the approval ends with the window or once it is lapsed.
+    #[max_len(11)]
+    pub signer_revisions: Vec<u64>,
+    pub signers_valid_from: i64,
+    pub signers_valid_until: Option<i64>,
+    // Set by `lapse_multisig_approval` after a signer's entry was removed, closed or re-windowed
+    pub lapsed: bool,
+    pub bump: u8,
+}
+
+impl MultisigApproval {
+    pub const MAX_SIGNERS: usize = MAX_SIGNERS;
+
+    /// Reads the m-of-n configuration of a token-2022 multisig, `None` for any other account
+    pub fn read_multisig(info: &AccountInfo) -> Result<Option<(u8, Vec<Pubkey>)>> {
+        if info.owner != &spl_token_2022::ID || info.data_len() != Multisig::LEN {
+            return Ok(None);
+        }
+
+        let multisig = Multisig::unpack(&info.try_borrow_data()?)?;
+        Ok(Some((multisig.m, multisig.signers[..multisig.n as usize].to_vec())))
+    }
+
+    /// Whether the approval resolved by the hook covers `owner`: it exists and `owner` is still the
+    /// multisig it approved, with the same signers and threshold. An approval that requires
+    /// whitelisted signers must also not be lapsed and be within its signers' window.
+    pub fn covers(approval: &AccountInfo, owner: &AccountInfo, now: i64) -> Result<bool> {
+        if approval.owner != &crate::ID || approval.data_is_empty() {
+            return Ok(false);
+        }
+
+        let approval = MultisigApproval::try_deserialize(&mut &approval.try_borrow_data()?[..])?;
+
+        let same_multisig = match Self::read_multisig(owner)? {
+            Some((m, signers)) => approval.multisig == owner.key() && approval.m == m && approval.signers == signers,
+            None => false,
+        };
+
+        Ok(same_multisig && (!approval.signers_whitelisted || approval.signers_still_whitelisted(now)))
+    }
+
+    fn signers_still_whitelisted(&self, now: i64) -> bool {
+        let before_end = match self.signers_valid_until {
+            Some(until) => now < until,
+            None => true,
+        };
+        !self.lapsed && now >= self.signers_valid_from && before_end
+    }
+}
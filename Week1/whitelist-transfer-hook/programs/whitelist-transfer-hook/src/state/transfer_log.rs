@@ -0,0 +1,112 @@
+use std::mem::size_of;
+
+use anchor_lang::prelude::*;
+
+use crate::state::TransferPolicy;
+
+/// Rule under which the hook let a transfer through
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum TransferDecision {
+    /// The mint is paused and the recovery wallet is a party to the transfer
+    Recovery,
+    /// The owners' entries satisfied the mint's policy
+    Policy(TransferPolicy),
+}
+
+impl TransferDecision {
+    /// Single byte stored in a `TransferRecord`: 0 for recovery, 1 + the policy otherwise
+    pub fn to_code(self) -> u8 {
+        match self {
+            TransferDecision::Recovery => 0,
+            TransferDecision::Policy(policy) => 1 + policy as u8,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        let policy = match code {
+            0 => return Some(TransferDecision::Recovery),
+            1 => TransferPolicy::AllowlistSource,
+            2 => TransferPolicy::AllowlistBoth,
+            3 => TransferPolicy::AllowlistEither,
+            4 => TransferPolicy::Blocklist,
+            5 => TransferPolicy::Open,
+            _ => return None,
+        };
+        Some(TransferDecision::Policy(policy))
+    }
+}
+
+/// One logged transfer. Plain bytes only, so the hook can write it in place.
+#[zero_copy]
+#[derive(Debug, PartialEq, Eq)]
+pub struct TransferRecord {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub source_owner: Pubkey,
+    pub destination_owner: Pubkey,
+    pub amount: u64,
+    pub slot: u64,
+    pub decision: u8,                // `TransferDecision::to_code`
+    pub source_whitelisted: u8,      // 1 when the source side counted as whitelisted
+    pub destination_whitelisted: u8, // 1 when the destination side counted as whitelisted
+    pub padding: [u8; 5],
+}
+
+impl TransferRecord {
+    pub fn decision(&self) -> Option<TransferDecision> {
+        TransferDecision::from_code(self.decision)
+    }
+}
+
+/// Ring buffer of the last transfers of a mint, written by the hook when it exists.
+/// Zero-copy, so a transfer costs one record write instead of decoding and re-encoding the log.
+#[account(zero_copy)]
+pub struct TransferLog {
+    pub mint: Pubkey,
+    pub records: [TransferRecord; 32],
+    pub head: u16, // Index the next record is written to
+    pub len: u16,  // Records written so far, up to CAPACITY
+    pub bump: u8,
+    pub padding: [u8; 3],
+}
+
+impl TransferLog {
+    pub const CAPACITY: usize = 32;
+    pub const SPACE: usize = 8 + size_of::<TransferLog>();
+
+    /// Stores `record`, overwriting the oldest one once the log is full
+    pub fn push(&mut self, record: TransferRecord) {
+        let head = self.head as usize;
+        self.records[head] = record;
+        self.head = ((head + 1) % Self::CAPACITY) as u16;
+        self.len = (self.len + 1).min(Self::CAPACITY as u16);
+    }
+
+    /// Records in the order they were written, oldest first
+    pub fn records(&self) -> impl Iterator<Item = &TransferRecord> {
+        let len = self.len as usize;
+        let start = if len < Self::CAPACITY { 0 } else { self.head as usize };
+        (0..len).map(move |i| &self.records[(start + i) % Self::CAPACITY])
+    }
+
+    /// Appends `record` to the log resolved by the hook, nothing to do if the admin never created it.
+    /// The account is optional, so it is loaded in place here the way `AccountLoader::load_mut` does.
+    pub fn append(info: &AccountInfo, record: TransferRecord) -> Result<()> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(());
+        }
+
+        let mut data = info.try_borrow_mut_data()?;
+        require!(data.len() >= Self::SPACE, ErrorCode::AccountDidNotDeserialize);
+        require!(
+            data.starts_with(<TransferLog as Discriminator>::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+
+        let log: &mut TransferLog = bytemuck::from_bytes_mut(&mut data[8..Self::SPACE]);
+        log.push(record);
+
+        Ok(())
+    }
+}
+
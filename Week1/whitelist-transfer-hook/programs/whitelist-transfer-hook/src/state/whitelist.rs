@@ -3,7 +3,69 @@ use anchor_lang::prelude::*;
 #[account]
 #[derive(InitSpace)]
 pub struct Whitelist {
+    pub mint: Pubkey,
     pub address: Pubkey,
     pub is_whitelisted: bool,
+    // Unix timestamps bounding when the entry counts as whitelisted, no upper bound when `None`
+    pub valid_from: i64,
+    pub valid_until: Option<i64>,
+    // Bumped whenever the entry is removed or given a new window, so multisig approvals that
+    // checked it can be lapsed
+    pub revision: u64,
     pub bump: u8,
-}
\ No newline at end of file
+}
+
+/// State of an existing entry as seen by the transfer hook
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EntryStatus {
+    Whitelisted,
+    NotWhitelisted,
+    /// Whitelisted, but the current time is outside `valid_from..valid_until`
+    OutsideWindow,
+}
+
+impl EntryStatus {
+    /// The stricter of two statuses, for transfers that require both owners of an entry to pass.
+    /// An explicit rejection outranks an expired entry, which outranks having no entry at all.
+    pub fn stricter(a: Option<EntryStatus>, b: Option<EntryStatus>) -> Option<EntryStatus> {
+        let rank = |status: Option<EntryStatus>| match status {
+            Some(EntryStatus::NotWhitelisted) => 3,
+            Some(EntryStatus::OutsideWindow) => 2,
+            None => 1,
+            Some(EntryStatus::Whitelisted) => 0,
+        };
+        if rank(b) > rank(a) { b } else { a }
+    }
+}
+
+impl Whitelist {
+    /// Records that the entry may have stopped counting as whitelisted
+    pub fn note_change(&mut self) {
+        self.revision = self.revision.wrapping_add(1);
+    }
+
+    pub fn is_within_window(&self, now: i64) -> bool {
+        let before_end = match self.valid_until {
+            Some(until) => now < until,
+            None => true,
+        };
+        now >= self.valid_from && before_end
+    }
+
+    /// Reads an entry resolved by the hook, `None` if the entry was never created or has been closed
+    pub fn status(info: &AccountInfo, now: i64) -> Result<Option<EntryStatus>> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(None);
+        }
+
+        let whitelist = Whitelist::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+
+        let status = match (whitelist.is_whitelisted, whitelist.is_within_window(now)) {
+            (false, _) => EntryStatus::NotWhitelisted,
+            (true, true) => EntryStatus::Whitelisted,
+            (true, false) => EntryStatus::OutsideWindow,
+        };
+
+        Ok(Some(status))
+    }
+}
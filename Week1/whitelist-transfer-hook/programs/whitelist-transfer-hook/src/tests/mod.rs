@@ -0,0 +1,2222 @@
+#[cfg(test)]
+mod transfer_hook_tests {
+
+    use {
+        anchor_lang::{
+            prelude::{msg, Clock},
+            solana_program::{
+                bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+                instruction::{error::InstructionError, AccountMeta, Instruction},
+                program_pack::Pack,
+                pubkey::Pubkey,
+                system_instruction,
+                system_program::ID as SYSTEM_PROGRAM_ID,
+            },
+            AccountDeserialize,
+            Discriminator,
+            InstructionData,
+            ToAccountMetas,
+        },
+        anchor_spl::{
+            associated_token::{
+                get_associated_token_address_with_program_id,
+                spl_associated_token_account::instruction::create_associated_token_account,
+            },
+            token_2022::spl_token_2022::{
+                self,
+                extension::{
+                    transfer_hook::{self, TransferHook},
+                    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+                },
+                state::{Account as TokenAccount, Mint, Multisig},
+                ID as TOKEN_2022_PROGRAM_ID,
+            },
+        },
+        litesvm::{types::FailedTransactionMetadata, LiteSVM},
+        spl_tlv_account_resolution::{seeds::Seed, account::ExtraAccountMeta, state::ExtraAccountMetaList},
+        spl_transfer_hook_interface::instruction::ExecuteInstruction,
+        solana_keypair::Keypair,
+        solana_message::Message,
+        solana_native_token::LAMPORTS_PER_SOL,
+        solana_signer::Signer,
+        solana_transaction::Transaction,
+        solana_transaction_error::TransactionError,
+        std::path::PathBuf,
+    };
+
+    use crate::errors::WhitelistTransferHookError;
+
+    static PROGRAM_ID: Pubkey = crate::ID;
+
+    const DECIMALS: u8 = 9;
+
+    // Setup function to initialize LiteSVM, load the hook program and fund an admin keypair
+    fn setup() -> (LiteSVM, Keypair) {
+        let mut program = LiteSVM::new();
+        let admin = Keypair::new();
+
+        program
+            .airdrop(&admin.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to admin");
+
+        let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../target/deploy/whitelist_transfer_hook.so");
+
+        let program_data = std::fs::read(so_path).expect("Failed to read program SO file");
+
+        program
+            .add_program(PROGRAM_ID, &program_data)
+            .expect("Failed to add program");
+        set_upgrade_authority(&mut program, &admin.pubkey());
+
+        (program, admin)
+    }
+
+    // Sends the instructions in a single transaction paid by the first signer
+    fn send(
+        program: &mut LiteSVM,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), Box<FailedTransactionMetadata>> {
+        let message = Message::new(instructions, Some(&signers[0].pubkey()));
+        let transaction = Transaction::new(signers, message, program.latest_blockhash());
+
+        let tx = program.send_transaction(transaction).map_err(Box::new)?;
+        msg!("CUs Consumed: {}", tx.compute_units_consumed);
+
+        Ok(())
+    }
+
+    // Decodes the hook error a failed transaction was rejected with
+    fn hook_error(
+        result: Result<(), Box<FailedTransactionMetadata>>,
+    ) -> Option<WhitelistTransferHookError> {
+        match result.err()?.err {
+            TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+                WhitelistTransferHookError::from_code(code)
+            }
+            _ => None,
+        }
+    }
+
+    // Creates a Token-2022 mint whose TransferHook extension points at this program
+    fn create_hooked_mint(program: &mut LiteSVM, payer: &Keypair) -> Pubkey {
+        let mint = Keypair::new();
+        let space = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook])
+            .unwrap();
+
+        let instructions = [
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &mint.pubkey(),
+                program.minimum_balance_for_rent_exemption(space),
+                space as u64,
+                &TOKEN_2022_PROGRAM_ID,
+            ),
+            transfer_hook::instruction::initialize(
+                &TOKEN_2022_PROGRAM_ID,
+                &mint.pubkey(),
+                Some(payer.pubkey()),
+                Some(PROGRAM_ID),
+            )
+            .unwrap(),
+            spl_token_2022::instruction::initialize_mint2(
+                &TOKEN_2022_PROGRAM_ID,
+                &mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                DECIMALS,
+            )
+            .unwrap(),
+        ];
+
+        send(program, &instructions, &[payer, &mint]).unwrap();
+
+        mint.pubkey()
+    }
+
+    fn config_pda() -> Pubkey {
+        Pubkey::find_program_address(&[b"config"], &PROGRAM_ID).0
+    }
+
+    fn program_data_pda() -> Pubkey {
+        Pubkey::find_program_address(&[PROGRAM_ID.as_ref()], &bpf_loader_upgradeable::ID).0
+    }
+
+    // Stores the ProgramData account an upgradeable deployment of the hook would have, LiteSVM loads
+    // the program without one
+    fn set_upgrade_authority(program: &mut LiteSVM, authority: &Pubkey) {
+        let mut data = Vec::with_capacity(UpgradeableLoaderState::size_of_programdata_metadata());
+        data.extend_from_slice(&3u32.to_le_bytes()); // UpgradeableLoaderState::ProgramData
+        data.extend_from_slice(&0u64.to_le_bytes()); // slot
+        data.push(1); // Some(upgrade_authority_address)
+        data.extend_from_slice(authority.as_ref());
+
+        program
+            .set_account(
+                program_data_pda(),
+                solana_account::Account {
+                    lamports: program.minimum_balance_for_rent_exemption(data.len()),
+                    data,
+                    owner: bpf_loader_upgradeable::ID,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .unwrap();
+    }
+
+    fn initialize_config_ix(admin: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::InitializeConfig {
+                admin: *admin,
+                config: config_pda(),
+                program_data: program_data_pda(),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::InitializeConfig {}.data(),
+        }
+    }
+
+    fn initialize_whitelist_ix(operator: &Pubkey, mint: &Pubkey, user: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::InitializeWhitelist {
+                operator: *operator,
+                mint: *mint,
+                mint_config: mint_config_pda(mint),
+                whitelist: whitelist_pda(mint, user),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::InitializeWhitelist { user: *user }.data(),
+        }
+    }
+
+    // Builds add_to_whitelist / remove_from_whitelist from their instruction data
+    fn whitelist_operations_ix(
+        operator: &Pubkey,
+        mint: &Pubkey,
+        user: &Pubkey,
+        data: Vec<u8>,
+    ) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::WhitelistOperations {
+                operator: *operator,
+                mint: *mint,
+                mint_config: mint_config_pda(mint),
+                whitelist: whitelist_pda(mint, user),
+                user: *user,
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data,
+        }
+    }
+
+    fn set_paused_ix(pauser: &Pubkey, mint: &Pubkey, paused: bool) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::SetPaused {
+                pauser: *pauser,
+                mint: *mint,
+                mint_config: mint_config_pda(mint),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::SetPaused { paused }.data(),
+        }
+    }
+
+    // Attaches the hook to `mint`, approved by the deployment admin `admin`
+    fn initialize_transfer_hook_ix(payer: &Pubkey, admin: &Pubkey, mint: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::InitializeExtraAccountMetaList {
+                payer: *payer,
+                admin: *admin,
+                config: config_pda(),
+                extra_account_meta_list: extra_account_meta_list_pda(mint),
+                mint: *mint,
+                mint_config: mint_config_pda(mint),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::InitializeTransferHook {}.data(),
+        }
+    }
+
+    // Builds a mint config admin instruction (set_policy, set_paused, ...) from its data
+    fn mint_config_operations_ix(admin: &Pubkey, mint: &Pubkey, data: Vec<u8>) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::MintConfigOperations {
+                admin: *admin,
+                mint: *mint,
+                mint_config: mint_config_pda(mint),
+            }
+            .to_account_metas(None),
+            data,
+        }
+    }
+
+    fn attesters_pda(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"attesters", mint.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn credential_pda(mint: &Pubkey, subject: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"credential", mint.as_ref(), subject.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn exempt_authorities_pda(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"exempt_authorities", mint.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn mint_config_pda(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"mint_config", mint.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn multisig_approval_pda(mint: &Pubkey, multisig: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"multisig", mint.as_ref(), multisig.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn transfer_log_pda(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"transfer_log", mint.as_ref()], &PROGRAM_ID).0
+    }
+
+    // Reads the zero-copy transfer log of `mint`
+    fn load_transfer_log(program: &LiteSVM, mint: &Pubkey) -> crate::state::TransferLog {
+        let account = program.get_account(&transfer_log_pda(mint)).unwrap();
+        assert_eq!(&account.data[..8], crate::state::TransferLog::DISCRIMINATOR);
+        *bytemuck::from_bytes(&account.data[8..crate::state::TransferLog::SPACE])
+    }
+
+    fn initialize_transfer_log_ix(admin: &Pubkey, mint: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::InitializeTransferLog {
+                admin: *admin,
+                mint: *mint,
+                mint_config: mint_config_pda(mint),
+                transfer_log: transfer_log_pda(mint),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::InitializeTransferLog {}.data(),
+        }
+    }
+
+    fn whitelist_pda(mint: &Pubkey, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"whitelist", mint.as_ref(), user.as_ref()], &PROGRAM_ID).0
+    }
+
+    fn extra_account_meta_list_pda(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], &PROGRAM_ID).0
+    }
+
+    // Builds a transfer_checked with the accounts the hook needs appended
+    fn hooked_transfer(
+        mint: &Pubkey,
+        source: &Pubkey,
+        destination: &Pubkey,
+        owner: &Pubkey,
+        destination_owner: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        delegated_transfer(mint, source, destination, owner, owner, destination_owner, amount, &[])
+    }
+
+    // Same, signed by `authority`, which is a delegate unless it is `source_owner`. `multisig_signers`
+    // sign for an authority that is a multisig.
+    #[allow(clippy::too_many_arguments)]
+    fn delegated_transfer(
+        mint: &Pubkey,
+        source: &Pubkey,
+        destination: &Pubkey,
+        source_owner: &Pubkey,
+        authority: &Pubkey,
+        destination_owner: &Pubkey,
+        amount: u64,
+        multisig_signers: &[&Pubkey],
+    ) -> Instruction {
+        let mut transfer_ix = spl_token_2022::instruction::transfer_checked(
+            &TOKEN_2022_PROGRAM_ID,
+            source,
+            mint,
+            destination,
+            authority,
+            multisig_signers,
+            amount,
+            DECIMALS,
+        )
+        .unwrap();
+
+        transfer_ix.accounts.extend([
+            AccountMeta::new_readonly(whitelist_pda(mint, authority), false),
+            AccountMeta::new_readonly(whitelist_pda(mint, destination_owner), false),
+            AccountMeta::new_readonly(mint_config_pda(mint), false),
+            AccountMeta::new_readonly(exempt_authorities_pda(mint), false),
+            AccountMeta::new_readonly(attesters_pda(mint), false),
+            AccountMeta::new_readonly(credential_pda(mint, authority), false),
+            AccountMeta::new_readonly(credential_pda(mint, destination_owner), false),
+            AccountMeta::new_readonly(*destination_owner, false),
+            AccountMeta::new(transfer_log_pda(mint), false),
+            AccountMeta::new_readonly(*source_owner, false),
+            AccountMeta::new_readonly(whitelist_pda(mint, source_owner), false),
+            AccountMeta::new_readonly(credential_pda(mint, source_owner), false),
+            AccountMeta::new_readonly(multisig_approval_pda(mint, authority), false),
+            AccountMeta::new_readonly(multisig_approval_pda(mint, destination_owner), false),
+            AccountMeta::new_readonly(multisig_approval_pda(mint, source_owner), false),
+            AccountMeta::new_readonly(PROGRAM_ID, false),
+            AccountMeta::new_readonly(extra_account_meta_list_pda(mint), false),
+        ]);
+
+        transfer_ix
+    }
+
+    fn token_balance(program: &LiteSVM, token_account: &Pubkey) -> u64 {
+        let account = program.get_account(token_account).unwrap();
+        StateWithExtensions::<TokenAccount>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    // Creates a hooked mint with its extra account metas, funds the admin's token account and
+    // opens one for the recipient. Returns the mint, source and destination token accounts
+    fn setup_hooked_mint(
+        program: &mut LiteSVM,
+        admin: &Keypair,
+        recipient: &Pubkey,
+    ) -> (Pubkey, Pubkey, Pubkey) {
+        let mint = create_hooked_mint(program, admin);
+        let source = get_associated_token_address_with_program_id(
+            &admin.pubkey(),
+            &mint,
+            &TOKEN_2022_PROGRAM_ID,
+        );
+        let destination =
+            get_associated_token_address_with_program_id(recipient, &mint, &TOKEN_2022_PROGRAM_ID);
+
+        let instructions = [
+            // The admin is both the deployment admin and the mint authority
+            initialize_config_ix(&admin.pubkey()),
+            create_associated_token_account(
+                &admin.pubkey(),
+                &admin.pubkey(),
+                &mint,
+                &TOKEN_2022_PROGRAM_ID,
+            ),
+            create_associated_token_account(&admin.pubkey(), recipient, &mint, &TOKEN_2022_PROGRAM_ID),
+            spl_token_2022::instruction::mint_to(
+                &TOKEN_2022_PROGRAM_ID,
+                &mint,
+                &source,
+                &admin.pubkey(),
+                &[],
+                100 * 10u64.pow(DECIMALS as u32),
+            )
+            .unwrap(),
+            // Store the extra accounts the hook resolves on every transfer
+            initialize_transfer_hook_ix(&admin.pubkey(), &admin.pubkey(), &mint),
+        ];
+
+        send(program, &instructions, &[admin]).unwrap();
+
+        (mint, source, destination)
+    }
+
+    #[test]
+    fn test_hooked_transfer_checked() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+
+        let (mint, source, destination) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+
+        // Whitelist the source owner
+        send(
+            &mut program,
+            &[
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &admin.pubkey()),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::AddToWhitelist { user: admin.pubkey() }.data(),
+                ),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        // A whitelisted owner can transfer
+        let amount = 10u64.pow(DECIMALS as u32);
+        let transfer_ix = hooked_transfer(
+            &mint,
+            &source,
+            &destination,
+            &admin.pubkey(),
+            &recipient.pubkey(),
+            amount,
+        );
+
+        send(&mut program, std::slice::from_ref(&transfer_ix), &[&admin]).unwrap();
+
+        assert_eq!(token_balance(&program, &source), 99 * amount);
+        assert_eq!(token_balance(&program, &destination), amount);
+
+        // Once removed from the whitelist, the same transfer is rejected by the hook
+        let remove_from_whitelist_ix = whitelist_operations_ix(
+            &admin.pubkey(),
+            &mint,
+            &admin.pubkey(),
+            crate::instruction::RemoveFromWhitelist { user: admin.pubkey() }.data(),
+        );
+
+        send(&mut program, &[remove_from_whitelist_ix], &[&admin]).unwrap();
+
+        program.expire_blockhash();
+        assert!(
+            matches!(
+                hook_error(send(&mut program, &[transfer_ix], &[&admin])),
+                Some(WhitelistTransferHookError::NotWhitelisted)
+            ),
+            "Transfer should fail once the owner is no longer whitelisted"
+        );
+        assert_eq!(token_balance(&program, &destination), amount);
+    }
+
+    #[test]
+    fn test_error_codes_round_trip() {
+        for &error in WhitelistTransferHookError::ALL {
+            let decoded = WhitelistTransferHookError::from_code(error.into()).unwrap();
+            assert_eq!(decoded.name(), error.name());
+        }
+
+        // The IDL lists one error per `#[msg]` variant of errors.rs, ALL must cover each of them
+        let idl_errors = include_str!("../errors.rs").matches("#[msg(\"").count();
+        assert_eq!(WhitelistTransferHookError::ALL.len(), idl_errors);
+
+        // A variant missing from ALL would shift the last code away from the array length
+        let last = *WhitelistTransferHookError::ALL.last().unwrap();
+        assert_eq!(
+            u32::from(last),
+            anchor_lang::error::ERROR_CODE_OFFSET + WhitelistTransferHookError::ALL.len() as u32 - 1
+        );
+        assert!(WhitelistTransferHookError::from_code(anchor_lang::error::ERROR_CODE_OFFSET - 1).is_none());
+    }
+
+    #[test]
+    fn test_whitelists_are_per_mint() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+        let issuer = Keypair::new();
+
+        program
+            .airdrop(&issuer.pubkey(), 10 * LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to issuer");
+
+        let (mint_a, source_a, destination_a) =
+            setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+        let mint_b = create_hooked_mint(&mut program, &issuer);
+
+        // The deployment admin approves every mint the hook serves
+        assert!(
+            matches!(
+                hook_error(send(
+                    &mut program,
+                    &[initialize_transfer_hook_ix(&issuer.pubkey(), &issuer.pubkey(), &mint_b)],
+                    &[&issuer],
+                )),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Attaching the hook should require the deployment admin"
+        );
+
+        // Each mint authority becomes the admin of its own mint's whitelist
+        send(
+            &mut program,
+            &[initialize_transfer_hook_ix(&issuer.pubkey(), &admin.pubkey(), &mint_b)],
+            &[&issuer, &admin],
+        )
+        .unwrap();
+
+        let mint_config_account = program.get_account(&mint_config_pda(&mint_b)).unwrap();
+        let mint_config =
+            crate::state::MintConfig::try_deserialize(&mut mint_config_account.data.as_ref()).unwrap();
+        assert_eq!(mint_config.admin, issuer.pubkey());
+
+        // The admin of mint A cannot manage the whitelist of mint B
+        assert!(
+            send(
+                &mut program,
+                &[initialize_whitelist_ix(&admin.pubkey(), &mint_b, &admin.pubkey())],
+                &[&admin],
+            )
+            .is_err(),
+            "Only the mint's admin should manage its whitelist"
+        );
+
+        // Whitelisting the owner on mint B does not allow transfers of mint A
+        send(
+            &mut program,
+            &[
+                initialize_whitelist_ix(&issuer.pubkey(), &mint_b, &admin.pubkey()),
+                whitelist_operations_ix(
+                    &issuer.pubkey(),
+                    &mint_b,
+                    &admin.pubkey(),
+                    crate::instruction::AddToWhitelist { user: admin.pubkey() }.data(),
+                ),
+            ],
+            &[&issuer],
+        )
+        .unwrap();
+
+        let amount = 10u64.pow(DECIMALS as u32);
+        assert!(
+            send(
+                &mut program,
+                &[hooked_transfer(
+                    &mint_a,
+                    &source_a,
+                    &destination_a,
+                    &admin.pubkey(),
+                    &recipient.pubkey(),
+                    amount,
+                )],
+                &[&admin],
+            )
+            .is_err(),
+            "A whitelist entry on another mint should not allow the transfer"
+        );
+    }
+
+    #[test]
+    fn test_transfer_policies() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+
+        let (mint, source, destination) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+
+        // Only the source owner is whitelisted, the recipient has a non-whitelisted entry
+        send(
+            &mut program,
+            &[
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &admin.pubkey()),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::AddToWhitelist { user: admin.pubkey() }.data(),
+                ),
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &recipient.pubkey()),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        let set_policy_ix = |policy: crate::state::TransferPolicy| {
+            mint_config_operations_ix(
+                &admin.pubkey(),
+                &mint,
+                crate::instruction::SetPolicy { policy }.data(),
+            )
+        };
+
+        let amount = 10u64.pow(DECIMALS as u32);
+        let transfer_ix = hooked_transfer(
+            &mint,
+            &source,
+            &destination,
+            &admin.pubkey(),
+            &recipient.pubkey(),
+            amount,
+        );
+
+        let cases = [
+            (crate::state::TransferPolicy::AllowlistSource, true),
+            (crate::state::TransferPolicy::AllowlistBoth, false),
+            (crate::state::TransferPolicy::AllowlistEither, true),
+            (crate::state::TransferPolicy::Blocklist, false),
+            (crate::state::TransferPolicy::Open, true),
+        ];
+
+        for (policy, allowed) in cases {
+            program.expire_blockhash();
+            send(&mut program, &[set_policy_ix(policy)], &[&admin]).unwrap();
+
+            let before = token_balance(&program, &destination);
+            let result = send(&mut program, std::slice::from_ref(&transfer_ix), &[&admin]);
+
+            assert_eq!(result.is_ok(), allowed, "Unexpected outcome under {:?}", policy);
+            let expected = if allowed { before + amount } else { before };
+            assert_eq!(token_balance(&program, &destination), expected);
+        }
+    }
+
+    #[test]
+    fn test_pause_with_recovery_wallet() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+        let recovery = Keypair::new();
+
+        let (mint, source, destination) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+        let recovery_token = get_associated_token_address_with_program_id(
+            &recovery.pubkey(),
+            &mint,
+            &TOKEN_2022_PROGRAM_ID,
+        );
+
+        send(
+            &mut program,
+            &[
+                create_associated_token_account(
+                    &admin.pubkey(),
+                    &recovery.pubkey(),
+                    &mint,
+                    &TOKEN_2022_PROGRAM_ID,
+                ),
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &admin.pubkey()),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::AddToWhitelist { user: admin.pubkey() }.data(),
+                ),
+                mint_config_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    crate::instruction::SetRecoveryWallet { recovery_wallet: Some(recovery.pubkey()) }
+                        .data(),
+                ),
+                set_paused_ix(&admin.pubkey(), &mint, true),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        let amount = 10u64.pow(DECIMALS as u32);
+
+        // A whitelisted owner is stopped while the mint is paused
+        assert!(
+            send(
+                &mut program,
+                &[hooked_transfer(
+                    &mint,
+                    &source,
+                    &destination,
+                    &admin.pubkey(),
+                    &recipient.pubkey(),
+                    amount,
+                )],
+                &[&admin],
+            )
+            .is_err(),
+            "Transfers should be rejected while paused"
+        );
+
+        // Funds can still be moved to the recovery wallet
+        send(
+            &mut program,
+            &[hooked_transfer(
+                &mint,
+                &source,
+                &recovery_token,
+                &admin.pubkey(),
+                &recovery.pubkey(),
+                amount,
+            )],
+            &[&admin],
+        )
+        .unwrap();
+        assert_eq!(token_balance(&program, &recovery_token), amount);
+
+        // Unpausing restores normal transfers
+        send(
+            &mut program,
+            &[
+                set_paused_ix(&admin.pubkey(), &mint, false),
+                hooked_transfer(
+                    &mint,
+                    &source,
+                    &destination,
+                    &admin.pubkey(),
+                    &recipient.pubkey(),
+                    amount,
+                ),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+        assert_eq!(token_balance(&program, &destination), amount);
+    }
+
+    #[test]
+    fn test_roles() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+        let operator = Keypair::new();
+        let pauser = Keypair::new();
+        for member in [&operator, &pauser] {
+            program
+                .airdrop(&member.pubkey(), LAMPORTS_PER_SOL)
+                .expect("Failed to airdrop SOL to role member");
+        }
+
+        let (mint, _, _) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+
+        // Nobody but the admin holds a role yet
+        assert!(
+            matches!(
+                hook_error(send(
+                    &mut program,
+                    &[initialize_whitelist_ix(&operator.pubkey(), &mint, &recipient.pubkey())],
+                    &[&operator],
+                )),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Whitelist changes should need the operator role"
+        );
+
+        send(
+            &mut program,
+            &[
+                mint_config_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    crate::instruction::GrantRole {
+                        role: crate::state::Role::WhitelistOperator,
+                        member: operator.pubkey(),
+                    }
+                    .data(),
+                ),
+                mint_config_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    crate::instruction::GrantRole {
+                        role: crate::state::Role::Pauser,
+                        member: pauser.pubkey(),
+                    }
+                    .data(),
+                ),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        // Each role only unlocks its own instructions
+        send(
+            &mut program,
+            &[
+                initialize_whitelist_ix(&operator.pubkey(), &mint, &recipient.pubkey()),
+                whitelist_operations_ix(
+                    &operator.pubkey(),
+                    &mint,
+                    &recipient.pubkey(),
+                    crate::instruction::AddToWhitelist { user: recipient.pubkey() }.data(),
+                ),
+            ],
+            &[&operator],
+        )
+        .unwrap();
+        assert!(
+            send(&mut program, &[set_paused_ix(&operator.pubkey(), &mint, true)], &[&operator]).is_err(),
+            "Operator should not pause transfers"
+        );
+        assert!(
+            send(
+                &mut program,
+                &[whitelist_operations_ix(
+                    &pauser.pubkey(),
+                    &mint,
+                    &recipient.pubkey(),
+                    crate::instruction::RemoveFromWhitelist { user: recipient.pubkey() }.data(),
+                )],
+                &[&pauser],
+            )
+            .is_err(),
+            "Pauser should not change whitelist entries"
+        );
+        send(&mut program, &[set_paused_ix(&pauser.pubkey(), &mint, true)], &[&pauser]).unwrap();
+
+        let mint_config_account = program.get_account(&mint_config_pda(&mint)).unwrap();
+        let mint_config =
+            crate::state::MintConfig::try_deserialize(&mut mint_config_account.data.as_ref()).unwrap();
+        assert!(mint_config.paused);
+        assert_eq!(mint_config.roles.len(), 2);
+
+        // Only the admin manages roles
+        let revoke_ix = |signer: &Pubkey| {
+            mint_config_operations_ix(
+                signer,
+                &mint,
+                crate::instruction::RevokeRole {
+                    role: crate::state::Role::WhitelistOperator,
+                    member: operator.pubkey(),
+                }
+                .data(),
+            )
+        };
+        assert!(
+            send(&mut program, &[revoke_ix(&operator.pubkey())], &[&operator]).is_err(),
+            "Operator should not manage roles"
+        );
+        send(&mut program, &[revoke_ix(&admin.pubkey())], &[&admin]).unwrap();
+
+        program.expire_blockhash();
+        assert!(
+            matches!(
+                hook_error(send(
+                    &mut program,
+                    &[whitelist_operations_ix(
+                        &operator.pubkey(),
+                        &mint,
+                        &recipient.pubkey(),
+                        crate::instruction::RemoveFromWhitelist { user: recipient.pubkey() }.data(),
+                    )],
+                    &[&operator],
+                )),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Revoked operator should lose access"
+        );
+    }
+
+    // Writes an account at `address` owned by `owner`, as a program-owned PDA would be
+    fn set_program_account(program: &mut LiteSVM, address: &Pubkey, owner: &Pubkey) {
+        program
+            .set_account(
+                *address,
+                solana_account::Account {
+                    lamports: LAMPORTS_PER_SOL,
+                    data: vec![0; 64],
+                    owner: *owner,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .unwrap();
+    }
+
+    fn exempt_authority_ix(admin: &Pubkey, mint: &Pubkey, data: Vec<u8>) -> Instruction {
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::ExemptAuthorityOperations {
+                admin: *admin,
+                mint: *mint,
+                mint_config: mint_config_pda(mint),
+                exempt_authorities: exempt_authorities_pda(mint),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data,
+        }
+    }
+
+    #[test]
+    fn test_exempt_authority() {
+        let (mut program, admin) = setup();
+        let pool_program = Pubkey::new_unique();
+        let pool = Pubkey::find_program_address(&[b"pool"], &pool_program).0;
+
+        // A pool state PDA owned by a DeFi program, holding the mint through its token account
+        set_program_account(&mut program, &pool, &pool_program);
+
+        let (mint, source, pool_token) = setup_hooked_mint(&mut program, &admin, &pool);
+
+        // Both sides must be whitelisted, and the pool cannot go through the user flow
+        send(
+            &mut program,
+            &[
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &admin.pubkey()),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::AddToWhitelist { user: admin.pubkey() }.data(),
+                ),
+                mint_config_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    crate::instruction::SetPolicy {
+                        policy: crate::state::TransferPolicy::AllowlistBoth,
+                    }
+                    .data(),
+                ),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        let amount = 10u64.pow(DECIMALS as u32);
+        let transfer_ix = hooked_transfer(&mint, &source, &pool_token, &admin.pubkey(), &pool, amount);
+
+        assert!(
+            matches!(
+                hook_error(send(&mut program, std::slice::from_ref(&transfer_ix), &[&admin])),
+                Some(WhitelistTransferHookError::DestinationNotWhitelisted)
+            ),
+            "Pool should not receive tokens before it is exempt"
+        );
+
+        send(
+            &mut program,
+            &[
+                exempt_authority_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    crate::instruction::AddExemptAuthority { authority: pool }.data(),
+                ),
+                transfer_ix.clone(),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+        assert_eq!(token_balance(&program, &pool_token), amount);
+
+        // Keypairs can never be exempt, their holder signs anything
+        assert!(
+            matches!(
+                hook_error(send(
+                    &mut program,
+                    &[exempt_authority_ix(
+                        &admin.pubkey(),
+                        &mint,
+                        crate::instruction::AddExemptAuthority { authority: admin.pubkey() }.data(),
+                    )],
+                    &[&admin],
+                )),
+                Some(WhitelistTransferHookError::InvalidExemptAuthority)
+            ),
+            "Exempting a keypair should be rejected"
+        );
+
+        // Once removed, the pool is back to needing a whitelist entry
+        program.expire_blockhash();
+        send(
+            &mut program,
+            &[exempt_authority_ix(
+                &admin.pubkey(),
+                &mint,
+                crate::instruction::RemoveExemptAuthority { authority: pool }.data(),
+            )],
+            &[&admin],
+        )
+        .unwrap();
+        assert!(
+            send(&mut program, &[transfer_ix], &[&admin]).is_err(),
+            "Pool should not receive tokens once it is removed"
+        );
+    }
+
+    #[test]
+    fn test_exempt_authority_rejects_foreign_pdas() {
+        let (mut program, admin) = setup();
+        let pool_program = Pubkey::new_unique();
+        let pool = Pubkey::find_program_address(&[b"pool"], &pool_program).0;
+        set_program_account(&mut program, &pool, &pool_program);
+
+        // Another program's PDA, which that program assigned to the pool program. Its deriving program
+        // can still sign for it, and its owner now matches the pool's.
+        let attacker_program = Pubkey::new_unique();
+        let foreign = Pubkey::find_program_address(&[b"vault"], &attacker_program).0;
+        set_program_account(&mut program, &foreign, &pool_program);
+
+        let (mint, source, _) = setup_hooked_mint(&mut program, &admin, &pool);
+        let foreign_token =
+            get_associated_token_address_with_program_id(&foreign, &mint, &TOKEN_2022_PROGRAM_ID);
+
+        send(
+            &mut program,
+            &[
+                create_associated_token_account(&admin.pubkey(), &foreign, &mint, &TOKEN_2022_PROGRAM_ID),
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &admin.pubkey()),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::AddToWhitelist { user: admin.pubkey() }.data(),
+                ),
+                mint_config_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    crate::instruction::SetPolicy {
+                        policy: crate::state::TransferPolicy::AllowlistBoth,
+                    }
+                    .data(),
+                ),
+                exempt_authority_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    crate::instruction::AddExemptAuthority { authority: pool }.data(),
+                ),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+        assert_eq!(program.get_account(&foreign).unwrap().owner, pool_program);
+
+        // Only the registered address is exempt, not everything its program owns
+        let amount = 10u64.pow(DECIMALS as u32);
+        assert!(
+            matches!(
+                hook_error(send(
+                    &mut program,
+                    &[hooked_transfer(&mint, &source, &foreign_token, &admin.pubkey(), &foreign, amount)],
+                    &[&admin],
+                )),
+                Some(WhitelistTransferHookError::DestinationNotWhitelisted)
+            ),
+            "A foreign PDA assigned to the pool program should not bypass the whitelist"
+        );
+        assert_eq!(token_balance(&program, &foreign_token), 0);
+    }
+
+    #[test]
+    fn test_attested_transfer() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+        let attester = Keypair::new();
+        program
+            .airdrop(&attester.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to attester");
+
+        let (mint, source, destination) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+
+        const KYC_LEVEL_1: u16 = 1;
+        let expires_at = program.get_sysvar::<Clock>().unix_timestamp + 86_400;
+
+        let attester_operations_ix = |data: Vec<u8>| Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::AttesterOperations {
+                admin: admin.pubkey(),
+                mint,
+                mint_config: mint_config_pda(&mint),
+                attesters: attesters_pda(&mint),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data,
+        };
+        let issue_credential_ix = |issuer: &Pubkey, claim: u16| Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::IssueCredential {
+                issuer: *issuer,
+                mint,
+                attesters: attesters_pda(&mint),
+                credential: credential_pda(&mint, &admin.pubkey()),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::IssueCredential {
+                subject: admin.pubkey(),
+                claim,
+                expires_at,
+            }
+            .data(),
+        };
+
+        let amount = 10u64.pow(DECIMALS as u32);
+        let transfer_ix = hooked_transfer(&mint, &source, &destination, &admin.pubkey(), &recipient.pubkey(), amount);
+
+        // The admin has no whitelist entry for this mint
+        assert!(
+            matches!(
+                hook_error(send(&mut program, std::slice::from_ref(&transfer_ix), &[&admin])),
+                Some(WhitelistTransferHookError::NotWhitelisted)
+            ),
+            "Transfer without entry or credential should fail"
+        );
+
+        // Only registered attesters may issue credentials
+        assert!(
+            matches!(
+                hook_error(send(
+                    &mut program,
+                    &[issue_credential_ix(&attester.pubkey(), KYC_LEVEL_1)],
+                    &[&attester],
+                )),
+                Some(WhitelistTransferHookError::UntrustedAttester)
+            ),
+            "Unregistered attester should not issue credentials"
+        );
+
+        send(
+            &mut program,
+            &[
+                attester_operations_ix(crate::instruction::AddAttester { attester: attester.pubkey() }.data()),
+                attester_operations_ix(crate::instruction::SetRequiredClaim { claim: KYC_LEVEL_1 }.data()),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        // A credential for another claim does not count
+        send(&mut program, &[issue_credential_ix(&attester.pubkey(), 2)], &[&attester]).unwrap();
+        assert!(
+            send(&mut program, std::slice::from_ref(&transfer_ix), &[&admin]).is_err(),
+            "Credential with the wrong claim should not allow the transfer"
+        );
+
+        program.expire_blockhash();
+        send(
+            &mut program,
+            &[issue_credential_ix(&attester.pubkey(), KYC_LEVEL_1), transfer_ix.clone()],
+            &[&attester, &admin],
+        )
+        .unwrap();
+        assert_eq!(token_balance(&program, &destination), amount);
+
+        let credential_account = program.get_account(&credential_pda(&mint, &admin.pubkey())).unwrap();
+        let credential = crate::state::Credential::try_deserialize(&mut credential_account.data.as_ref()).unwrap();
+        assert_eq!(credential.issuer, attester.pubkey());
+        assert_eq!(credential.claim, KYC_LEVEL_1);
+
+        // Once revoked, the credential no longer stands in for a whitelist entry
+        send(
+            &mut program,
+            &[Instruction {
+                program_id: PROGRAM_ID,
+                accounts: crate::accounts::RevokeCredential {
+                    issuer: attester.pubkey(),
+                    mint,
+                    credential: credential_pda(&mint, &admin.pubkey()),
+                }
+                .to_account_metas(None),
+                data: crate::instruction::RevokeCredential { subject: admin.pubkey() }.data(),
+            }],
+            &[&attester],
+        )
+        .unwrap();
+
+        program.expire_blockhash();
+        assert!(
+            matches!(
+                hook_error(send(&mut program, &[transfer_ix], &[&admin])),
+                Some(WhitelistTransferHookError::NotWhitelisted)
+            ),
+            "Transfer with a revoked credential should fail"
+        );
+    }
+
+    #[test]
+    fn test_transfer_log() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+
+        let (mint, source, destination) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+
+        send(
+            &mut program,
+            &[
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &admin.pubkey()),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::AddToWhitelist { user: admin.pubkey() }.data(),
+                ),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        // Transfers before the log exists are only emitted as events
+        let amount = 10u64.pow(DECIMALS as u32);
+        send(
+            &mut program,
+            &[hooked_transfer(&mint, &source, &destination, &admin.pubkey(), &recipient.pubkey(), amount)],
+            &[&admin],
+        )
+        .unwrap();
+
+        send(
+            &mut program,
+            &[
+                initialize_transfer_log_ix(&admin.pubkey(), &mint),
+                hooked_transfer(&mint, &source, &destination, &admin.pubkey(), &recipient.pubkey(), 2 * amount),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        let log = load_transfer_log(&program, &mint);
+        assert_eq!(log.mint, mint);
+        assert_eq!(log.head, 1);
+        assert_eq!(log.len, 1);
+
+        let record = log.records[0];
+        assert_eq!(record.source, source);
+        assert_eq!(record.destination, destination);
+        assert_eq!(record.source_owner, admin.pubkey());
+        assert_eq!(record.destination_owner, recipient.pubkey());
+        assert_eq!(record.amount, 2 * amount);
+        assert_eq!(
+            record.decision(),
+            Some(crate::state::TransferDecision::Policy(crate::state::TransferPolicy::AllowlistSource))
+        );
+        assert_eq!(record.source_whitelisted, 1);
+        assert_eq!(record.destination_whitelisted, 0);
+    }
+
+    #[test]
+    fn test_transfer_log_compute_budget() {
+        // The default budget of a single instruction, which a wallet's plain transfer_checked gets
+        const TRANSFER_COMPUTE_BUDGET: u64 = 200_000;
+
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+
+        let (mint, source, destination) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+
+        send(
+            &mut program,
+            &[
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &admin.pubkey()),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::AddToWhitelist { user: admin.pubkey() }.data(),
+                ),
+                initialize_transfer_log_ix(&admin.pubkey(), &mint),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        // Fill the log and keep going, writes into a full log must cost no more than the first ones
+        let capacity = crate::state::TransferLog::CAPACITY as u64;
+        for amount in 1..=capacity + 2 {
+            let message = Message::new(
+                &[hooked_transfer(&mint, &source, &destination, &admin.pubkey(), &recipient.pubkey(), amount)],
+                Some(&admin.pubkey()),
+            );
+            let transaction = Transaction::new(&[&admin], message, program.latest_blockhash());
+            let consumed = program.send_transaction(transaction).unwrap().compute_units_consumed;
+
+            assert!(
+                consumed <= TRANSFER_COMPUTE_BUDGET,
+                "Transfer {amount} consumed {consumed} compute units"
+            );
+        }
+
+        let log = load_transfer_log(&program, &mint);
+        assert_eq!(log.len as usize, crate::state::TransferLog::CAPACITY);
+        assert_eq!(log.head, 2);
+        assert_eq!(log.records().next().unwrap().amount, 3);
+        assert_eq!(log.records().last().unwrap().amount, capacity + 2);
+    }
+
+    #[test]
+    fn test_transfer_log_wraps_around() {
+        let mut log: crate::state::TransferLog = bytemuck::Zeroable::zeroed();
+        let record = |amount: u64| crate::state::TransferRecord {
+            source: Pubkey::default(),
+            destination: Pubkey::default(),
+            source_owner: Pubkey::default(),
+            destination_owner: Pubkey::default(),
+            amount,
+            slot: amount,
+            decision: crate::state::TransferDecision::Recovery.to_code(),
+            source_whitelisted: 0,
+            destination_whitelisted: 0,
+            padding: [0; 5],
+        };
+
+        let capacity = crate::state::TransferLog::CAPACITY as u64;
+        for amount in 0..capacity + 3 {
+            log.push(record(amount));
+        }
+
+        // The three oldest records were overwritten, the next write goes to the fourth slot
+        assert_eq!(log.len as usize, crate::state::TransferLog::CAPACITY);
+        assert_eq!(log.head, 3);
+        assert_eq!(log.records[0].amount, capacity);
+        assert_eq!(log.records[2].amount, capacity + 2);
+        assert_eq!(log.records[3].amount, 3);
+
+        // Reading back starts from the oldest record still kept
+        let amounts: Vec<u64> = log.records().map(|record| record.amount).collect();
+        assert_eq!(amounts, (3..capacity + 3).collect::<Vec<_>>());
+
+        // Every policy decision survives the single byte it is stored in
+        for policy in [
+            crate::state::TransferPolicy::AllowlistSource,
+            crate::state::TransferPolicy::AllowlistBoth,
+            crate::state::TransferPolicy::AllowlistEither,
+            crate::state::TransferPolicy::Blocklist,
+            crate::state::TransferPolicy::Open,
+        ] {
+            let decision = crate::state::TransferDecision::Policy(policy);
+            assert_eq!(crate::state::TransferDecision::from_code(decision.to_code()), Some(decision));
+        }
+    }
+
+    #[test]
+    fn test_delegated_transfer() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+        let delegate = Keypair::new();
+        program
+            .airdrop(&delegate.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to delegate");
+
+        let (mint, source, destination) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+        let amount = 10u64.pow(DECIMALS as u32);
+
+        // Only the owner is whitelisted, the delegate may move 5 tokens on its behalf
+        send(
+            &mut program,
+            &[
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &admin.pubkey()),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::AddToWhitelist { user: admin.pubkey() }.data(),
+                ),
+                spl_token_2022::instruction::approve(
+                    &TOKEN_2022_PROGRAM_ID,
+                    &source,
+                    &delegate.pubkey(),
+                    &admin.pubkey(),
+                    &[],
+                    5 * amount,
+                )
+                .unwrap(),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        let transfer_ix = delegated_transfer(
+            &mint,
+            &source,
+            &destination,
+            &admin.pubkey(),
+            &delegate.pubkey(),
+            &recipient.pubkey(),
+            amount,
+            &[],
+        );
+        let delegate_policy_ix = |delegate_policy: crate::state::DelegatePolicy| {
+            mint_config_operations_ix(
+                &admin.pubkey(),
+                &mint,
+                crate::instruction::SetDelegatePolicy { delegate_policy }.data(),
+            )
+        };
+
+        // By default both the delegate and the owner must pass
+        assert!(
+            matches!(
+                hook_error(send(&mut program, std::slice::from_ref(&transfer_ix), &[&delegate])),
+                Some(WhitelistTransferHookError::NotWhitelisted)
+            ),
+            "Delegate without entry should not transfer under the default policy"
+        );
+
+        send(&mut program, &[delegate_policy_ix(crate::state::DelegatePolicy::Owner)], &[&admin]).unwrap();
+        send(&mut program, std::slice::from_ref(&transfer_ix), &[&delegate]).unwrap();
+        assert_eq!(token_balance(&program, &destination), amount);
+
+        program.expire_blockhash();
+        send(&mut program, &[delegate_policy_ix(crate::state::DelegatePolicy::Delegate)], &[&admin]).unwrap();
+        assert!(
+            send(&mut program, std::slice::from_ref(&transfer_ix), &[&delegate]).is_err(),
+            "Delegate without entry should not transfer when its own entry counts"
+        );
+
+        // Once the delegate is whitelisted, it passes under every policy
+        send(
+            &mut program,
+            &[
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &delegate.pubkey()),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &delegate.pubkey(),
+                    crate::instruction::AddToWhitelist { user: delegate.pubkey() }.data(),
+                ),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+        send(&mut program, std::slice::from_ref(&transfer_ix), &[&delegate]).unwrap();
+
+        program.expire_blockhash();
+        send(
+            &mut program,
+            &[delegate_policy_ix(crate::state::DelegatePolicy::Both), transfer_ix],
+            &[&admin, &delegate],
+        )
+        .unwrap();
+        assert_eq!(token_balance(&program, &destination), 3 * amount);
+    }
+
+    #[test]
+    fn test_multisig_owner() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+        let multisig = Keypair::new();
+        let signers: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+        let signer_pubkeys: Vec<Pubkey> = signers.iter().map(|signer| signer.pubkey()).collect();
+        let signer_keys: Vec<&Pubkey> = signer_pubkeys.iter().collect();
+
+        let (mint, _, destination) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+        let multisig_token =
+            get_associated_token_address_with_program_id(&multisig.pubkey(), &mint, &TOKEN_2022_PROGRAM_ID);
+        let amount = 10u64.pow(DECIMALS as u32);
+
+        // A 2-of-3 multisig holding tokens of the mint
+        let rent = program.minimum_balance_for_rent_exemption(Multisig::LEN);
+        send(
+            &mut program,
+            &[
+                system_instruction::create_account(
+                    &admin.pubkey(),
+                    &multisig.pubkey(),
+                    rent,
+                    Multisig::LEN as u64,
+                    &TOKEN_2022_PROGRAM_ID,
+                ),
+                spl_token_2022::instruction::initialize_multisig2(
+                    &TOKEN_2022_PROGRAM_ID,
+                    &multisig.pubkey(),
+                    &signer_keys,
+                    2,
+                )
+                .unwrap(),
+                create_associated_token_account(
+                    &admin.pubkey(),
+                    &multisig.pubkey(),
+                    &mint,
+                    &TOKEN_2022_PROGRAM_ID,
+                ),
+                spl_token_2022::instruction::mint_to(
+                    &TOKEN_2022_PROGRAM_ID,
+                    &mint,
+                    &multisig_token,
+                    &admin.pubkey(),
+                    &[],
+                    10 * amount,
+                )
+                .unwrap(),
+            ],
+            &[&admin, &multisig],
+        )
+        .unwrap();
+
+        let transfer_ix = delegated_transfer(
+            &mint,
+            &multisig_token,
+            &destination,
+            &multisig.pubkey(),
+            &multisig.pubkey(),
+            &recipient.pubkey(),
+            amount,
+            &signer_keys[..2],
+        );
+        let approve_ix = |require_whitelisted_signers: bool| {
+            let mut accounts = crate::accounts::ApproveMultisig {
+                operator: admin.pubkey(),
+                mint,
+                mint_config: mint_config_pda(&mint),
+                multisig: multisig.pubkey(),
+                multisig_approval: multisig_approval_pda(&mint, &multisig.pubkey()),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None);
+            if require_whitelisted_signers {
+                accounts.extend(
+                    signer_keys
+                        .iter()
+                        .map(|signer| AccountMeta::new_readonly(whitelist_pda(&mint, signer), false)),
+                );
+            }
+            Instruction {
+                program_id: PROGRAM_ID,
+                accounts,
+                data: crate::instruction::ApproveMultisig { require_whitelisted_signers }.data(),
+            }
+        };
+
+        assert!(
+            matches!(
+                hook_error(send(
+                    &mut program,
+                    std::slice::from_ref(&transfer_ix),
+                    &[&admin, &signers[0], &signers[1]],
+                )),
+                Some(WhitelistTransferHookError::NotWhitelisted)
+            ),
+            "Multisig should not transfer before it is approved"
+        );
+
+        // Signers without entries block an approval that requires them
+        assert!(
+            matches!(
+                hook_error(send(&mut program, &[approve_ix(true)], &[&admin])),
+                Some(WhitelistTransferHookError::SignerNotWhitelisted)
+            ),
+            "Approval should require every signer to be whitelisted"
+        );
+
+        send(&mut program, &[approve_ix(false), transfer_ix.clone()], &[&admin, &signers[0], &signers[1]]).unwrap();
+        assert_eq!(token_balance(&program, &destination), amount);
+
+        // With every signer whitelisted, the stricter approval goes through
+        let mut instructions = Vec::new();
+        for signer in &signer_keys {
+            instructions.push(initialize_whitelist_ix(&admin.pubkey(), &mint, signer));
+            instructions.push(whitelist_operations_ix(
+                &admin.pubkey(),
+                &mint,
+                signer,
+                crate::instruction::AddToWhitelist { user: **signer }.data(),
+            ));
+        }
+        instructions.push(approve_ix(true));
+        send(&mut program, &instructions, &[&admin]).unwrap();
+
+        let approval_account = program.get_account(&multisig_approval_pda(&mint, &multisig.pubkey())).unwrap();
+        let approval =
+            crate::state::MultisigApproval::try_deserialize(&mut approval_account.data.as_ref()).unwrap();
+        assert_eq!(approval.m, 2);
+        assert_eq!(approval.signers.len(), 3);
+        assert!(approval.signers_whitelisted);
+
+        program.expire_blockhash();
+        send(&mut program, std::slice::from_ref(&transfer_ix), &[&admin, &signers[0], &signers[1]]).unwrap();
+        assert_eq!(token_balance(&program, &destination), 2 * amount);
+
+        let lapse_ix = |signer: &Pubkey| Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::LapseMultisigApproval {
+                mint,
+                multisig_approval: multisig_approval_pda(&mint, &multisig.pubkey()),
+                signer_whitelist: whitelist_pda(&mint, signer),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::LapseMultisigApproval { signer: *signer }.data(),
+        };
+
+        // Removing an entry that is not one of the signers leaves the approval alone
+        send(
+            &mut program,
+            &[
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::RemoveFromWhitelist { user: admin.pubkey() }.data(),
+                ),
+                transfer_ix.clone(),
+            ],
+            &[&admin, &signers[0], &signers[1]],
+        )
+        .unwrap();
+        assert_eq!(token_balance(&program, &destination), 3 * amount);
+        assert!(
+            matches!(
+                hook_error(send(&mut program, &[lapse_ix(signer_keys[2])], &[&admin])),
+                Some(WhitelistTransferHookError::SignerEntryUnchanged)
+            ),
+            "An approval should not lapse while its signers' entries are unchanged"
+        );
+        assert!(
+            matches!(
+                hook_error(send(&mut program, &[lapse_ix(&admin.pubkey())], &[&admin])),
+                Some(WhitelistTransferHookError::NotAMultisigSigner)
+            ),
+            "Only a signer's entry should lapse the approval"
+        );
+
+        // Removing a signer lapses the approval, and it cannot be approved again
+        let remove_signer_ix = whitelist_operations_ix(
+            &admin.pubkey(),
+            &mint,
+            signer_keys[2],
+            crate::instruction::RemoveFromWhitelist { user: *signer_keys[2] }.data(),
+        );
+        send(&mut program, &[remove_signer_ix, lapse_ix(signer_keys[2])], &[&admin]).unwrap();
+
+        program.expire_blockhash();
+        assert!(
+            matches!(
+                hook_error(send(
+                    &mut program,
+                    std::slice::from_ref(&transfer_ix),
+                    &[&admin, &signers[0], &signers[1]],
+                )),
+                Some(WhitelistTransferHookError::NotWhitelisted)
+            ),
+            "Multisig should not transfer once one of its signers is removed"
+        );
+        assert!(
+            matches!(
+                hook_error(send(&mut program, &[approve_ix(true)], &[&admin])),
+                Some(WhitelistTransferHookError::SignerNotWhitelisted)
+            ),
+            "Approval should not be renewed while a signer is not whitelisted"
+        );
+
+        // Whitelisting the signer again and renewing the approval restores the multisig
+        send(
+            &mut program,
+            &[
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    signer_keys[2],
+                    crate::instruction::AddToWhitelist { user: *signer_keys[2] }.data(),
+                ),
+                approve_ix(true),
+                transfer_ix.clone(),
+            ],
+            &[&admin, &signers[0], &signers[1]],
+        )
+        .unwrap();
+        assert_eq!(token_balance(&program, &destination), 4 * amount);
+
+        // An approval lasts only as long as the shortest signer entry
+        let mut clock = program.get_sysvar::<Clock>();
+        let valid_until = clock.unix_timestamp + 60;
+        send(
+            &mut program,
+            &[
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    signer_keys[0],
+                    crate::instruction::SetWhitelistValidity {
+                        user: *signer_keys[0],
+                        valid_from: 0,
+                        valid_until: Some(valid_until),
+                    }
+                    .data(),
+                ),
+                approve_ix(true),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        clock.unix_timestamp = valid_until;
+        program.set_sysvar::<Clock>(&clock);
+        program.expire_blockhash();
+        assert!(
+            send(&mut program, std::slice::from_ref(&transfer_ix), &[&admin, &signers[0], &signers[1]]).is_err(),
+            "Multisig should not transfer once a signer's entry has expired"
+        );
+
+        // Revoking the approval stops the multisig again
+        send(
+            &mut program,
+            &[Instruction {
+                program_id: PROGRAM_ID,
+                accounts: crate::accounts::RevokeMultisig {
+                    operator: admin.pubkey(),
+                    mint,
+                    mint_config: mint_config_pda(&mint),
+                    multisig_approval: multisig_approval_pda(&mint, &multisig.pubkey()),
+                }
+                .to_account_metas(None),
+                data: crate::instruction::RevokeMultisig {}.data(),
+            }],
+            &[&admin],
+        )
+        .unwrap();
+
+        program.expire_blockhash();
+        assert!(
+            send(&mut program, &[transfer_ix], &[&admin, &signers[0], &signers[1]]).is_err(),
+            "Multisig should not transfer once its approval is revoked"
+        );
+    }
+
+    #[test]
+    fn test_whitelist_validity_window() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+
+        let (mint, source, destination) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+
+        let mut clock = program.get_sysvar::<Clock>();
+        clock.unix_timestamp = 1_000_000;
+        program.set_sysvar::<Clock>(&clock);
+
+        // Whitelisted for one year from now
+        let one_year = 365 * 24 * 60 * 60;
+        send(
+            &mut program,
+            &[
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &admin.pubkey()),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::AddToWhitelist { user: admin.pubkey() }.data(),
+                ),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::SetWhitelistValidity {
+                        user: admin.pubkey(),
+                        valid_from: clock.unix_timestamp,
+                        valid_until: Some(clock.unix_timestamp + one_year),
+                    }
+                    .data(),
+                ),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        let amount = 10u64.pow(DECIMALS as u32);
+        let transfer_ix = hooked_transfer(
+            &mint,
+            &source,
+            &destination,
+            &admin.pubkey(),
+            &recipient.pubkey(),
+            amount,
+        );
+
+        send(&mut program, std::slice::from_ref(&transfer_ix), &[&admin]).unwrap();
+        assert_eq!(token_balance(&program, &destination), amount);
+
+        // After the approval expires, the same owner is rejected without any removal
+        clock.unix_timestamp += one_year;
+        program.set_sysvar::<Clock>(&clock);
+        program.expire_blockhash();
+
+        assert!(
+            matches!(
+                hook_error(send(&mut program, &[transfer_ix], &[&admin])),
+                Some(WhitelistTransferHookError::OutsideValidityWindow)
+            ),
+            "Transfer should fail once the whitelist entry has expired"
+        );
+        assert_eq!(token_balance(&program, &destination), amount);
+    }
+
+    #[test]
+    fn test_close_whitelist_entry() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+        let rent_receiver = Keypair::new();
+
+        let (mint, source, destination) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+
+        send(
+            &mut program,
+            &[
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &admin.pubkey()),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::AddToWhitelist { user: admin.pubkey() }.data(),
+                ),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        let whitelist = whitelist_pda(&mint, &admin.pubkey());
+        let rent = program.get_account(&whitelist).unwrap().lamports;
+
+        let close_whitelist_entry_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::CloseWhitelistEntry {
+                operator: admin.pubkey(),
+                mint,
+                mint_config: mint_config_pda(&mint),
+                whitelist,
+                rent_receiver: rent_receiver.pubkey(),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::CloseWhitelistEntry { user: admin.pubkey() }.data(),
+        };
+
+        send(&mut program, &[close_whitelist_entry_ix], &[&admin]).unwrap();
+
+        assert!(program.get_account(&whitelist).is_none_or(|account| account.data.is_empty()));
+        assert_eq!(program.get_balance(&rent_receiver.pubkey()), Some(rent));
+
+        // A closed entry reads as not whitelisted instead of failing to deserialize
+        let amount = 10u64.pow(DECIMALS as u32);
+        let transfer_ix = hooked_transfer(
+            &mint,
+            &source,
+            &destination,
+            &admin.pubkey(),
+            &recipient.pubkey(),
+            amount,
+        );
+
+        assert!(
+            send(&mut program, std::slice::from_ref(&transfer_ix), &[&admin]).is_err(),
+            "Transfer should fail once the entry is closed"
+        );
+
+        send(
+            &mut program,
+            &[
+                mint_config_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    crate::instruction::SetPolicy { policy: crate::state::TransferPolicy::Open }.data(),
+                ),
+                transfer_ix,
+            ],
+            &[&admin],
+        )
+        .unwrap();
+        assert_eq!(token_balance(&program, &destination), amount);
+    }
+
+    #[test]
+    fn test_batch_whitelist_operations() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+
+        let (mint, _, _) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+
+        // One user already has an entry, the others are new
+        let users: Vec<Pubkey> = (0..4).map(|_| Keypair::new().pubkey()).collect();
+        send(
+            &mut program,
+            &[initialize_whitelist_ix(&admin.pubkey(), &mint, &users[0])],
+            &[&admin],
+        )
+        .unwrap();
+
+        let batch_ix = |data: Vec<u8>, users: &[Pubkey]| {
+            let mut accounts = crate::accounts::BatchWhitelistOperations {
+                operator: admin.pubkey(),
+                mint,
+                mint_config: mint_config_pda(&mint),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None);
+            accounts.extend(
+                users
+                    .iter()
+                    .map(|user| AccountMeta::new(whitelist_pda(&mint, user), false)),
+            );
+
+            Instruction {
+                program_id: PROGRAM_ID,
+                accounts,
+                data,
+            }
+        };
+
+        let is_whitelisted = |program: &LiteSVM, user: &Pubkey| {
+            let account = program.get_account(&whitelist_pda(&mint, user)).unwrap();
+            crate::state::Whitelist::try_deserialize(&mut account.data.as_ref())
+                .unwrap()
+                .is_whitelisted
+        };
+
+        // Someone sent lamports to a new user's PDA ahead of time, below rent exemption
+        program.airdrop(&whitelist_pda(&mint, &users[1]), 1_000).unwrap();
+
+        send(
+            &mut program,
+            &[batch_ix(
+                crate::instruction::BatchAddToWhitelist { users: users.clone() }.data(),
+                &users,
+            )],
+            &[&admin],
+        )
+        .unwrap();
+
+        assert!(users.iter().all(|user| is_whitelisted(&program, user)));
+
+        // The prefunded PDA was topped up and taken over rather than failing the batch
+        let prefunded = program.get_account(&whitelist_pda(&mint, &users[1])).unwrap();
+        assert_eq!(prefunded.owner, PROGRAM_ID);
+        assert!(prefunded.lamports >= program.minimum_balance_for_rent_exemption(prefunded.data.len()));
+
+        // Removing a subset leaves the others whitelisted
+        send(
+            &mut program,
+            &[batch_ix(
+                crate::instruction::BatchRemoveFromWhitelist { users: users[..2].to_vec() }.data(),
+                &users[..2],
+            )],
+            &[&admin],
+        )
+        .unwrap();
+
+        assert!(!is_whitelisted(&program, &users[0]));
+        assert!(!is_whitelisted(&program, &users[1]));
+        assert!(is_whitelisted(&program, &users[2]));
+        assert!(is_whitelisted(&program, &users[3]));
+
+        // Accounts that do not match the users are rejected
+        program.expire_blockhash();
+        assert!(
+            send(
+                &mut program,
+                &[batch_ix(
+                    crate::instruction::BatchAddToWhitelist { users: users[..1].to_vec() }.data(),
+                    &users[1..2],
+                )],
+                &[&admin],
+            )
+            .is_err(),
+            "Mismatched whitelist accounts should be rejected"
+        );
+    }
+
+    #[test]
+    fn test_update_extra_account_metas() {
+        let (mut program, admin) = setup();
+        let recipient = Keypair::new();
+
+        let (mint, source, destination) = setup_hooked_mint(&mut program, &admin, &recipient.pubkey());
+
+        send(
+            &mut program,
+            &[
+                initialize_whitelist_ix(&admin.pubkey(), &mint, &admin.pubkey()),
+                whitelist_operations_ix(
+                    &admin.pubkey(),
+                    &mint,
+                    &admin.pubkey(),
+                    crate::instruction::AddToWhitelist { user: admin.pubkey() }.data(),
+                ),
+            ],
+            &[&admin],
+        )
+        .unwrap();
+
+        // Rewind the list to what an older version of the hook stored: only the source whitelist
+        let legacy_metas = [ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: b"whitelist".to_vec(),
+                },
+                Seed::AccountKey { index: 1 },
+                Seed::AccountKey { index: 3 },
+            ],
+            false,
+            false,
+        )
+        .unwrap()];
+
+        let extra_account_meta_list = extra_account_meta_list_pda(&mint);
+        let mut account = program.get_account(&extra_account_meta_list).unwrap();
+        account.data = vec![0; ExtraAccountMetaList::size_of(legacy_metas.len()).unwrap()];
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut account.data, &legacy_metas).unwrap();
+        program.set_account(extra_account_meta_list, account).unwrap();
+
+        let update_extra_account_metas_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::UpdateExtraAccountMetaList {
+                admin: admin.pubkey(),
+                extra_account_meta_list,
+                mint,
+                mint_config: mint_config_pda(&mint),
+                system_program: SYSTEM_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::UpdateExtraAccountMetas {}.data(),
+        };
+
+        send(&mut program, &[update_extra_account_metas_ix], &[&admin]).unwrap();
+
+        // The list now matches the accounts the current hook expects, and transfers go through
+        let account = program.get_account(&extra_account_meta_list).unwrap();
+        let expected = crate::InitializeExtraAccountMetaList::extra_account_metas().unwrap();
+        assert_eq!(account.data.len(), ExtraAccountMetaList::size_of(expected.len()).unwrap());
+
+        let amount = 10u64.pow(DECIMALS as u32);
+        send(
+            &mut program,
+            &[hooked_transfer(
+                &mint,
+                &source,
+                &destination,
+                &admin.pubkey(),
+                &recipient.pubkey(),
+                amount,
+            )],
+            &[&admin],
+        )
+        .unwrap();
+        assert_eq!(token_balance(&program, &destination), amount);
+    }
+
+    #[test]
+    fn test_admin_handover() {
+        let (mut program, admin) = setup();
+        let new_admin = Keypair::new();
+
+        program
+            .airdrop(&new_admin.pubkey(), LAMPORTS_PER_SOL)
+            .expect("Failed to airdrop SOL to new admin");
+
+        // Only the upgrade authority can claim the admin role of a deployment
+        assert!(
+            matches!(
+                hook_error(send(&mut program, &[initialize_config_ix(&new_admin.pubkey())], &[&new_admin])),
+                Some(WhitelistTransferHookError::Unauthorized)
+            ),
+            "Only the upgrade authority should initialize the config"
+        );
+
+        // Initializes the config with the upgrade authority as admin
+        let (mint, _, _) = setup_hooked_mint(&mut program, &admin, &new_admin.pubkey());
+
+        let propose_admin_ix = |admin: &Pubkey, new_admin: &Pubkey| Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::ProposeAdmin {
+                admin: *admin,
+                config: config_pda(),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::ProposeAdmin { new_admin: *new_admin }.data(),
+        };
+
+        // Only the admin may propose a successor
+        assert!(
+            send(
+                &mut program,
+                &[propose_admin_ix(&new_admin.pubkey(), &new_admin.pubkey())],
+                &[&new_admin],
+            )
+            .is_err(),
+            "Non-admin should not be able to propose an admin"
+        );
+
+        let accept_admin_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::AcceptAdmin {
+                new_admin: new_admin.pubkey(),
+                config: config_pda(),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::AcceptAdmin {}.data(),
+        };
+
+        send(&mut program, &[propose_admin_ix(&admin.pubkey(), &new_admin.pubkey())], &[&admin])
+            .unwrap();
+        send(&mut program, &[accept_admin_ix], &[&new_admin]).unwrap();
+
+        let config_account = program.get_account(&config_pda()).unwrap();
+        let config = crate::state::Config::try_deserialize(&mut config_account.data.as_ref()).unwrap();
+        assert_eq!(config.admin, new_admin.pubkey());
+        assert_eq!(config.pending_admin, None);
+
+        // The previous admin has lost access
+        program.expire_blockhash();
+        assert!(
+            send(&mut program, &[propose_admin_ix(&admin.pubkey(), &admin.pubkey())], &[&admin])
+                .is_err(),
+            "Previous admin should no longer be authorized"
+        );
+
+        // The deployment admin rotates the admin of a mint
+        let set_mint_admin_ix = |admin: &Pubkey, new_admin: &Pubkey| Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::SetMintAdmin {
+                admin: *admin,
+                config: config_pda(),
+                mint,
+                mint_config: mint_config_pda(&mint),
+            }
+            .to_account_metas(None),
+            data: crate::instruction::SetMintAdmin { new_admin: *new_admin }.data(),
+        };
+
+        assert!(
+            send(&mut program, &[set_mint_admin_ix(&admin.pubkey(), &admin.pubkey())], &[&admin]).is_err(),
+            "Only the deployment admin should rotate a mint admin"
+        );
+        send(&mut program, &[set_mint_admin_ix(&new_admin.pubkey(), &new_admin.pubkey())], &[&new_admin])
+            .unwrap();
+
+        let mint_config_account = program.get_account(&mint_config_pda(&mint)).unwrap();
+        let mint_config =
+            crate::state::MintConfig::try_deserialize(&mut mint_config_account.data.as_ref()).unwrap();
+        assert_eq!(mint_config.admin, new_admin.pubkey());
+
+        // The new mint admin manages the mint, the previous one no longer does
+        let set_policy_data = crate::instruction::SetPolicy {
+            policy: crate::state::TransferPolicy::AllowlistBoth,
+        }
+        .data();
+        assert!(
+            send(
+                &mut program,
+                &[mint_config_operations_ix(&admin.pubkey(), &mint, set_policy_data.clone())],
+                &[&admin],
+            )
+            .is_err(),
+            "Previous mint admin should no longer be authorized"
+        );
+        send(
+            &mut program,
+            &[mint_config_operations_ix(&new_admin.pubkey(), &mint, set_policy_data)],
+            &[&new_admin],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_init_mint() {
+        let (mut program, user) = setup();
+        let mint = Keypair::new();
+
+        send(&mut program, &[initialize_config_ix(&user.pubkey())], &[&user]).unwrap();
+
+        let init_mint_ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: crate::accounts::TokenFactory {
+                user: user.pubkey(),
+                admin: user.pubkey(),
+                config: config_pda(),
+                mint: mint.pubkey(),
+                extra_account_meta_list: extra_account_meta_list_pda(&mint.pubkey()),
+                mint_config: mint_config_pda(&mint.pubkey()),
+                system_program: SYSTEM_PROGRAM_ID,
+                token_program: TOKEN_2022_PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::InitMint {
+                _decimals: DECIMALS,
+                metadata: Some(crate::TokenMetadataArgs {
+                    name: "Whitelisted Token".to_string(),
+                    symbol: "WLT".to_string(),
+                    uri: "https://example.com/wlt.json".to_string(),
+                }),
+            }
+            .data(),
+        };
+
+        send(&mut program, &[init_mint_ix], &[&user, &mint]).unwrap();
+
+        // The mint points its transfer hook at this program
+        let mint_account = program.get_account(&mint.pubkey()).unwrap();
+        let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+        let hook = mint_state.get_extension::<TransferHook>().unwrap();
+        assert_eq!(Option::<Pubkey>::from(hook.program_id), Some(PROGRAM_ID));
+        assert_eq!(mint_state.base.decimals, DECIMALS);
+
+        // The extra account metas were stored in the same instruction
+        let extra_account_meta_list = program
+            .get_account(&extra_account_meta_list_pda(&mint.pubkey()))
+            .unwrap();
+        assert_eq!(extra_account_meta_list.owner, PROGRAM_ID);
+    }
+}
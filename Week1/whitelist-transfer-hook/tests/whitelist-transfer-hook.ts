@@ -61,34 +61,85 @@ describe("whitelist-transfer-hook", () => {
   const whitelist = anchor.web3.PublicKey.findProgramAddressSync(
     [
       Buffer.from("whitelist"),
+      mint2022.publicKey.toBuffer(),
       provider.publicKey.toBuffer(),
     ],
     program.programId
   )[0];
 
-  it("Initializes the Whitelist", async () => {
-    const tx = await program.methods.initializeWhitelist(provider.publicKey)
-      .accountsPartial({
-        admin: provider.publicKey,
-        whitelist,
-        systemProgram: anchor.web3.SystemProgram.programId,
-      })
-      .rpc();
+  const destinationWhitelist = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("whitelist"),
+      mint2022.publicKey.toBuffer(),
+      recipient.publicKey.toBuffer(),
+    ],
+    program.programId
+  )[0];
 
-    console.log("\nWhitelist initialized:", whitelist.toBase58());
-    console.log("Transaction signature:", tx);
-  });
+  const mintConfig = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("mint_config"), mint2022.publicKey.toBuffer()],
+    program.programId
+  )[0];
 
-  it("Add user to whitelist", async () => {
-    const tx = await program.methods.addToWhitelist(provider.publicKey)
+  const exemptAuthorities = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("exempt_authorities"), mint2022.publicKey.toBuffer()],
+    program.programId
+  )[0];
+
+  const attesters = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("attesters"), mint2022.publicKey.toBuffer()],
+    program.programId
+  )[0];
+
+  const credential = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("credential"),
+      mint2022.publicKey.toBuffer(),
+      provider.publicKey.toBuffer(),
+    ],
+    program.programId
+  )[0];
+
+  const destinationCredential = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("credential"),
+      mint2022.publicKey.toBuffer(),
+      recipient.publicKey.toBuffer(),
+    ],
+    program.programId
+  )[0];
+
+  const transferLog = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("transfer_log"), mint2022.publicKey.toBuffer()],
+    program.programId
+  )[0];
+
+  const multisigApproval = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("multisig"),
+      mint2022.publicKey.toBuffer(),
+      provider.publicKey.toBuffer(),
+    ],
+    program.programId
+  )[0];
+
+  const destinationMultisigApproval = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("multisig"),
+      mint2022.publicKey.toBuffer(),
+      recipient.publicKey.toBuffer(),
+    ],
+    program.programId
+  )[0];
+
+  it("Initializes the Config", async () => {
+    const tx = await program.methods.initializeConfig()
       .accountsPartial({
         admin: provider.publicKey,
-        whitelist,
-        user: provider.publicKey,
       })
       .rpc();
 
-    console.log("\nUser added to whitelist:", provider.publicKey.toBase58());
+    console.log("\nConfig initialized, admin:", provider.publicKey.toBase58());
     console.log("Transaction signature:", tx);
   });
 
@@ -181,6 +232,7 @@ describe("whitelist-transfer-hook", () => {
       .initializeTransferHook()
       .accountsPartial({
         payer: wallet.publicKey,
+        admin: wallet.publicKey,
         mint: mint2022.publicKey,
         extraAccountMetaList: extraAccountMetaListPDA,
         systemProgram: SystemProgram.programId,
@@ -195,6 +247,34 @@ describe("whitelist-transfer-hook", () => {
     console.log('Transaction Signature:', initializeExtraAccountMetaListInstruction);
   });
 
+  it("Initializes the Whitelist", async () => {
+    const tx = await program.methods.initializeWhitelist(provider.publicKey)
+      .accountsPartial({
+        operator: provider.publicKey,
+        mint: mint2022.publicKey,
+        whitelist,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    console.log("\nWhitelist initialized:", whitelist.toBase58());
+    console.log("Transaction signature:", tx);
+  });
+
+  it("Add user to whitelist", async () => {
+    const tx = await program.methods.addToWhitelist(provider.publicKey)
+      .accountsPartial({
+        operator: provider.publicKey,
+        mint: mint2022.publicKey,
+        whitelist,
+        user: provider.publicKey,
+      })
+      .rpc();
+
+    console.log("\nUser added to whitelist:", provider.publicKey.toBase58());
+    console.log("Transaction signature:", tx);
+  });
+
   it('Transfer Hook with Extra Account Meta', async () => {
     // 1 tokens
     const amount = 1 * 10 ** 9;
@@ -219,6 +299,29 @@ describe("whitelist-transfer-hook", () => {
       { pubkey: extraAccountMetaListPDA, isSigner: false, isWritable: false },
       // Whitelist PDA (the extra account we defined)
       { pubkey: whitelist, isSigner: false, isWritable: false },
+      // Destination owner's Whitelist PDA, may not exist
+      { pubkey: destinationWhitelist, isSigner: false, isWritable: false },
+      // Mint config holding the transfer policy
+      { pubkey: mintConfig, isSigner: false, isWritable: false },
+      // Registry of exempt authorities, may not exist
+      { pubkey: exemptAuthorities, isSigner: false, isWritable: false },
+      // Trusted attesters and the owners' credentials, may not exist
+      { pubkey: attesters, isSigner: false, isWritable: false },
+      { pubkey: credential, isSigner: false, isWritable: false },
+      { pubkey: destinationCredential, isSigner: false, isWritable: false },
+      // Destination owner, read when it is a multisig
+      { pubkey: recipient.publicKey, isSigner: false, isWritable: false },
+      // Transfer log, written by the hook when it exists
+      { pubkey: transferLog, isSigner: false, isWritable: true },
+      // Source token account owner with its Whitelist PDA and credential, the same as the
+      // authority's since the owner signs
+      { pubkey: provider.publicKey, isSigner: false, isWritable: false },
+      { pubkey: whitelist, isSigner: false, isWritable: false },
+      { pubkey: credential, isSigner: false, isWritable: false },
+      // Multisig approvals of the authority, the destination owner and the source owner, may not exist
+      { pubkey: multisigApproval, isSigner: false, isWritable: false },
+      { pubkey: destinationMultisigApproval, isSigner: false, isWritable: false },
+      { pubkey: multisigApproval, isSigner: false, isWritable: false },
       // Transfer hook program
       { pubkey: program.programId, isSigner: false, isWritable: false },
     );
@@ -250,7 +353,8 @@ it("Remove user from whitelist", async () => {
   const tx = await program.methods
     .removeFromWhitelist(provider.publicKey)
     .accountsPartial({
-      admin: provider.publicKey,
+      operator: provider.publicKey,
+      mint: mint2022.publicKey,
       user: provider.publicKey,
       whitelist,
     })